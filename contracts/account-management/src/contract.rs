@@ -1,43 +1,67 @@
 use cosmwasm_std::{
     entry_point, StdResult, Response, DepsMut, Env, MessageInfo, Deps,
-    Binary, Empty, to_json_binary,
+    Binary, Empty, Reply, SubMsg, WasmMsg, to_json_binary,
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
+use semver::Version;
 
 use crate::error::ContractError;
-use crate::helpers::{map_validate, validate_addr, is_valid_threshold};
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{Policy, State, POLICY, STATE, TX_NEXT_ID};
+use crate::cw4::Cw4ExecuteMsg;
+use crate::helpers::{map_validate_signers, query_group_weight, to_tx_id, validate_addr};
+use crate::migrate::migrate_from_v0_1;
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use crate::state::{MsgType, Policy, State, POLICY, STATE, TX_NEXT_ID};
+use crate::{execute, query};
 use crate::execute::{
-    add_signers, change_admin, change_threshold, change_whitelist_enabled, execute_transaction, remove_signers, remove_transfer_limits, remove_whitelist_addresses, set_transfer_limits, set_whitelist_addresses, sign_transaction
+    accept_admin, add_signers, approve_recovery, cancel_admin_transfer, cancel_transaction, change_admin, dispatch_transaction, close_transaction, change_threshold, change_whitelist_enabled, execute_approved, execute_recovery, execute_transaction, grant_role, member_changed_hook, pause, propose_recovery, reject_transaction, remove_signers, remove_transfer_limits, remove_transfer_window, remove_whitelist_addresses, remove_allowance, revoke_role, set_allowance, set_allowed_msg_types, set_recovery_config, set_timelock, set_transfer_limits, set_transfer_window, set_whitelist_addresses, sign_transaction, tx_reply, unpause, unsign_transaction, veto_recovery
 };
 use crate::query::{
-    admin, signer_list, threshold, transfer_limits, tx_executions, whitelist_addresses, whitelist_enabled
+    admin, allowance, allowed_msg_types, list_proposals, list_voters, list_votes, pause_status, pending_admin, proposal, recovery, recovery_config, reverse_proposals, roles, signer_list, threshold, timelock, transfer_limits, transfer_window, tx_executions, voter, whitelist_addresses, whitelist_enabled
 };
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:cw-account-management";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 const INIT_TX_ID: u16 = 1;
+// transaction reply ids start from the first transaction id, so 0 is free for the group hook
+const GROUP_HOOK_REPLY_ID: u64 = 0;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response<Empty>, ContractError> {
-    if !is_valid_threshold(msg.threshold, msg.signers.len()) {
-        return Err(ContractError::InvalidThreshold {
-            threshold: msg.threshold,
-        });
-    }
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-    let cfg = State {
-        admin: validate_addr(deps.api, &msg.admin)?,
-        signers: map_validate(deps.api, &msg.signers)?,
+    // without an admin the account governs itself through its own transactions
+    let admin = match &msg.admin {
+        Some(admin) => validate_addr(deps.api, admin)?,
+        None => env.contract.address.clone(),
+    };
+    let group = match &msg.group {
+        Some(_) if !msg.signers.is_empty() => {
+            return Err(ContractError::SignersManagedByGroup {});
+        },
+        Some(group) => Some(validate_addr(deps.api, group)?),
+        None => None,
+    };
+    let mut cfg = State {
+        self_governed: msg.admin.is_none(),
+        recovery_config: None,
+        admin,
+        signers: vec![],
         threshold: msg.threshold,
+        default_expiry: msg.default_expiry,
+        open_execution: msg.open_execution,
+        group,
+        group_weight: 0,
     };
+    match &cfg.group {
+        Some(group) => cfg.group_weight = query_group_weight(&deps.querier, group)?,
+        None => cfg.upsert_signers(map_validate_signers(deps.api, &msg.signers)?),
+    }
+    cfg.validate_threshold(&cfg.threshold)?;
     STATE.save(deps.storage, &cfg)?;
     TX_NEXT_ID.save(deps.storage, &INIT_TX_ID)?;
 
@@ -45,46 +69,159 @@ pub fn instantiate(
         whitelist_enabled: msg.whitelist_enabled,
         whitelist_addresses: vec![],
         transfer_limits: vec![],
+        timelock: None,
+        allowed_msg_types: MsgType::defaults(),
     };
     POLICY.save(deps.storage, &policy)?;
-    Ok(Response::default())
+
+    // ask the group to report membership changes, the account must be allowed to add hooks to the
+    // group since open transactions would otherwise keep the votes of members who left
+    let mut resp = Response::default();
+    if let Some(group) = cfg.group {
+        let add_hook = WasmMsg::Execute {
+            contract_addr: group.into(),
+            msg: to_json_binary(&Cw4ExecuteMsg::AddHook {
+                addr: env.contract.address.into(),
+            })?,
+            funds: vec![],
+        };
+        resp = resp.add_submessage(SubMsg::reply_on_error(add_hook, GROUP_HOOK_REPLY_ID));
+    }
+    Ok(resp)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response<Empty>, ContractError> {
     match msg {
-        ExecuteMsg::ChangeAdmin { new_admin } => change_admin(deps, info, new_admin),
+        ExecuteMsg::ChangeAdmin { new_admin, expires } => change_admin(deps, env, info, new_admin, expires),
+        ExecuteMsg::AcceptAdmin {} => accept_admin(deps, env, info),
+        ExecuteMsg::CancelAdminTransfer {} => cancel_admin_transfer(deps, info),
+        ExecuteMsg::GrantRole { address, role } => grant_role(deps, info, address, role),
+        ExecuteMsg::RevokeRole { address, role } => revoke_role(deps, info, address, role),
         ExecuteMsg::ChangeThreshold { new_threshold } => change_threshold(deps, info, new_threshold),
         ExecuteMsg::ChangeWhitelistEnabled { enabled } => change_whitelist_enabled(deps, info, enabled),
         ExecuteMsg::AddSigners { signers } => add_signers(deps, info, signers),
         ExecuteMsg::RemoveSigners { signers } => remove_signers(deps, info, signers),
-        ExecuteMsg::ExecuteTransaction { msgs } => execute_transaction(deps, info, msgs),
-        ExecuteMsg::SignTransaction { tx_id } => sign_transaction(deps, info, tx_id),
+        ExecuteMsg::ExecuteTransaction { msgs, expires } => execute_transaction(deps, env, info, String::new(), String::new(), msgs, expires),
+        ExecuteMsg::SignTransaction { tx_id } => sign_transaction(deps, env, info, tx_id),
+        ExecuteMsg::UnsignTransaction { tx_id } => unsign_transaction(deps, env, info, tx_id),
+        ExecuteMsg::RejectTransaction { tx_id } => reject_transaction(deps, env, info, tx_id),
+        ExecuteMsg::CancelTransaction { tx_id } => cancel_transaction(deps, info, tx_id),
+        ExecuteMsg::ExecuteApproved { tx_id } => execute_approved(deps, env, info, tx_id),
         ExecuteMsg::SetWhitelistAddresses { addresses } => set_whitelist_addresses(deps, info, addresses),
         ExecuteMsg::RemoveWhitelistAddresses { addresses } => remove_whitelist_addresses(deps, info, addresses),
         ExecuteMsg::SetTransferLimits { coins } => set_transfer_limits(deps, info, coins),
         ExecuteMsg::RemoveTransferLimits { denoms } => remove_transfer_limits(deps, info, denoms),
+        ExecuteMsg::SetTimelock { timelock } => set_timelock(deps, info, timelock),
+        ExecuteMsg::SetRecoveryConfig { config } => set_recovery_config(deps, info, config),
+        ExecuteMsg::ProposeRecovery { signers, threshold } => propose_recovery(deps, env, info, signers, threshold),
+        ExecuteMsg::ApproveRecovery {} => approve_recovery(deps, env, info),
+        ExecuteMsg::VetoRecovery {} => veto_recovery(deps, info),
+        ExecuteMsg::ExecuteRecovery {} => execute_recovery(deps, env, info),
+        ExecuteMsg::Pause {} => pause(deps, env, info),
+        ExecuteMsg::Unpause {} => unpause(deps, info),
+        ExecuteMsg::Propose { title, description, msgs, latest } => execute_transaction(deps, env, info, title, description, msgs, latest),
+        ExecuteMsg::Vote { proposal_id, vote } => execute::vote(deps, env, info, to_tx_id(proposal_id)?, vote),
+        ExecuteMsg::Execute { proposal_id } => execute_approved(deps, env, info, to_tx_id(proposal_id)?),
+        ExecuteMsg::Close { proposal_id } => close_transaction(deps, env, to_tx_id(proposal_id)?),
+        ExecuteMsg::MemberChangedHook(hook) => member_changed_hook(deps, info, hook.diffs),
+        ExecuteMsg::SetAllowance { signer, limits, period } => set_allowance(deps, env, info, signer, limits, period),
+        ExecuteMsg::RemoveAllowance { signer } => remove_allowance(deps, info, signer),
+        ExecuteMsg::SetTransferWindow { limits, period } => set_transfer_window(deps, env, info, limits, period),
+        ExecuteMsg::RemoveTransferWindow {} => remove_transfer_window(deps, info),
+        ExecuteMsg::SetAllowedMsgTypes { msg_types } => set_allowed_msg_types(deps, info, msg_types),
+        ExecuteMsg::DispatchTransaction { tx_id } => dispatch_transaction(deps, env, info, tx_id),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(
     deps: Deps,
-    _env: Env,
+    env: Env,
     msg: QueryMsg,
 ) -> StdResult<Binary> {
     match msg {
         QueryMsg::Admin {} => to_json_binary(&admin(deps)?),
+        QueryMsg::PendingAdmin {} => to_json_binary(&pending_admin(deps)?),
         QueryMsg::Threshold {} => to_json_binary(&threshold(deps)?),
         QueryMsg::Signerlist {} => to_json_binary(&signer_list(deps)?),
         QueryMsg::WhitelistEnabled {  } => to_json_binary(&whitelist_enabled(deps)?),
-        QueryMsg::TxExecutions {} => to_json_binary(&tx_executions(deps)?),
+        QueryMsg::TxExecutions {} => to_json_binary(&tx_executions(deps, env)?),
         QueryMsg::WhitelistAddresses {} => to_json_binary(&whitelist_addresses(deps)?),
         QueryMsg::TransferLimits {  } => to_json_binary(&transfer_limits(deps)?),
+        QueryMsg::Timelock {} => to_json_binary(&timelock(deps)?),
+        QueryMsg::Roles {} => to_json_binary(&roles(deps)?),
+        QueryMsg::RecoveryConfig {} => to_json_binary(&recovery_config(deps)?),
+        QueryMsg::Recovery {} => to_json_binary(&recovery(deps)?),
+        QueryMsg::PauseStatus {} => to_json_binary(&pause_status(deps)?),
+        QueryMsg::Proposal { proposal_id } => to_json_binary(&proposal(deps, env, proposal_id)?),
+        QueryMsg::ListProposals { start_after, limit } => to_json_binary(&list_proposals(deps, env, start_after, limit)?),
+        QueryMsg::ReverseProposals { start_before, limit } => to_json_binary(&reverse_proposals(deps, env, start_before, limit)?),
+        QueryMsg::Vote { proposal_id, voter } => to_json_binary(&query::vote(deps, proposal_id, voter)?),
+        QueryMsg::ListVotes { proposal_id, start_after, limit } => to_json_binary(&list_votes(deps, proposal_id, start_after, limit)?),
+        QueryMsg::Voter { address } => to_json_binary(&voter(deps, address)?),
+        QueryMsg::ListVoters { start_after, limit } => to_json_binary(&list_voters(deps, start_after, limit)?),
+        QueryMsg::Allowance { signer } => to_json_binary(&allowance(deps, env, signer)?),
+        QueryMsg::TransferWindow {} => to_json_binary(&transfer_window(deps, env)?),
+        QueryMsg::AllowedMsgTypes {} => to_json_binary(&allowed_msg_types(deps)?),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(
+    deps: DepsMut,
+    _env: Env,
+    msg: Reply,
+) -> Result<Response<Empty>, ContractError> {
+    match msg.id {
+        GROUP_HOOK_REPLY_ID => Err(ContractError::GroupHookFailed {
+            error: msg.result.into_result().err().unwrap_or_default(),
+        }),
+        _ => tx_reply(deps, msg),
     }
-}
\ No newline at end of file
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(
+    deps: DepsMut,
+    env: Env,
+    _msg: MigrateMsg,
+) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::CannotMigrate {
+            previous_contract: stored.contract,
+        });
+    }
+
+    let stored_version = parse_version(&stored.version)?;
+    if stored_version > parse_version(CONTRACT_VERSION)? {
+        return Err(ContractError::CannotMigrateVersion {
+            previous_version: stored.version,
+        });
+    }
+
+    // run every migration newer than the stored version, oldest first
+    if stored_version < Version::new(0, 2, 0) {
+        migrate_from_v0_1(deps.storage, &env.block)?;
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(
+        Response::new()
+            .add_attribute("action", "migrate")
+            .add_attribute("from_version", stored.version)
+            .add_attribute("to_version", CONTRACT_VERSION)
+    )
+}
+
+fn parse_version(version: &str) -> Result<Version, ContractError> {
+    Version::parse(version).map_err(|_| ContractError::InvalidVersion {
+        version: version.to_string(),
+    })
+}
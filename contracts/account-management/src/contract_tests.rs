@@ -1,12 +1,22 @@
 use std::collections::HashSet;
 
-use cosmwasm_std::{coin, Addr, BankMsg, Coin, Empty, Uint128};
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{coin, from_json, to_json_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal, Empty, Uint128, WasmMsg};
+use cw20::Cw20ExecuteMsg;
 use cw_multi_test::{App, ContractWrapper, Executor, AppBuilder};
+use cw_utils::{Duration, Expiration, Threshold, ThresholdResponse};
 use crate::msg::{
-    AdminResponse, ExecuteMsg, InstantiateMsg, QueryMsg, SignerListResponse, ThresholdResponse, TransferLimitsResponse, TxExecutionsResponse, WhitelistAddressesResponse, WhitelistEnabledResponse
+    AdminResponse, AllowanceResponse, AllowedMsgTypesResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, PauseStatusResponse, PendingAdminResponse, QueryMsg, RecoveryResponse, RoleHolder, RolesResponse, Signer, SignerListResponse, TimelockResponse, TransferLimitsResponse, TransferWindowResponse, TxExecutionsResponse, WhitelistAddressesResponse, WhitelistEnabledResponse
 };
-use crate::contract::{instantiate, query, execute};
-use crate::state::TxStatus;
+use crate::contract::{instantiate, query, execute, migrate, reply};
+use crate::cw3::{
+    ProposalListResponse, ProposalResponse, Status, Vote, VoteInfo, VoteListResponse, VoterDetail,
+    VoterListResponse, VoterResponse,
+};
+use crate::migrate::v0_1;
+use crate::cw4::{Member, MemberChangedHookMsg};
+use crate::error::ContractError;
+use crate::state::{MsgResult, MsgType, Permission, RecoveryConfig, Role, TxStatus, TX_NEXT_ID};
 
 const ALICE: &str = "alice";
 const BOB: &str = "bob";
@@ -31,7 +41,7 @@ fn mock_app() -> App {
 fn query_admin() {
     let mut app = App::default();
 
-    let code = ContractWrapper::new(execute, instantiate, query);
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
     let code_id = app.store_code(Box::new(code));
 
     let addr = app
@@ -39,10 +49,13 @@ fn query_admin() {
             code_id,
             Addr::unchecked("owner"),
             &InstantiateMsg {
-                admin: ALICE.to_string(),
-                signers: vec![CARL.to_string()],
-                threshold: 1,
+                admin: Some(ALICE.to_string()),
+                signers: vec![Signer { addr: CARL.to_string(), weight: 1 }],
+                threshold: Threshold::AbsoluteCount { weight: 1 },
                 whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
             },
             &[],
             "Contract",
@@ -66,7 +79,7 @@ fn query_admin() {
 fn query_signer_list() {
     let mut app = App::default();
 
-    let code = ContractWrapper::new(execute, instantiate, query);
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
     let code_id = app.store_code(Box::new(code));
 
     let addr = app
@@ -74,10 +87,13 @@ fn query_signer_list() {
             code_id,
             Addr::unchecked("owner"),
             &InstantiateMsg {
-                admin: ALICE.to_string(),
-                signers: vec![CARL.to_string()],
-                threshold: 1,
+                admin: Some(ALICE.to_string()),
+                signers: vec![Signer { addr: CARL.to_string(), weight: 1 }],
+                threshold: Threshold::AbsoluteCount { weight: 1 },
                 whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
             },
             &[],
             "Contract",
@@ -92,7 +108,7 @@ fn query_signer_list() {
     assert_eq!(
         resp,
         SignerListResponse {
-            signers: vec![CARL.to_string()],
+            signers: vec![Signer { addr: CARL.to_string(), weight: 1 }],
         }
     )
 }
@@ -101,7 +117,7 @@ fn query_signer_list() {
 fn exec_change_admin() {
     let mut app = App::default();
 
-    let code = ContractWrapper::new(execute, instantiate, query);
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
     let code_id = app.store_code(Box::new(code));
 
     let addr = app
@@ -109,10 +125,13 @@ fn exec_change_admin() {
             code_id,
             Addr::unchecked( ALICE.to_string()),
             &InstantiateMsg {
-                admin: ALICE.to_string(),
-                signers: vec![CARL.to_string()],
-                threshold: 1,
+                admin: Some(ALICE.to_string()),
+                signers: vec![Signer { addr: CARL.to_string(), weight: 1 }],
+                threshold: Threshold::AbsoluteCount { weight: 1 },
                 whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
             },
             &[],
             "Contract",
@@ -133,6 +152,7 @@ fn exec_change_admin() {
 
     let msg: ExecuteMsg<Empty> = ExecuteMsg::ChangeAdmin { 
         new_admin: BOB.to_string(),
+        expires: None,
     };
     let _ = app
         .execute_contract(
@@ -141,6 +161,38 @@ fn exec_change_admin() {
             &msg,
             &[],
         ).unwrap();
+
+    let resp: AdminResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::Admin {})
+        .unwrap();
+    assert_eq!(
+        resp,
+        AdminResponse {
+            admin: ALICE.to_string(),
+        }
+    );
+
+    let resp: PendingAdminResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::PendingAdmin {})
+        .unwrap();
+    assert_eq!(
+        resp,
+        PendingAdminResponse {
+            pending_admin: Some(BOB.to_string()),
+            expires: Some(Expiration::Never {}),
+        }
+    );
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::AcceptAdmin {};
+    let _ = app
+        .execute_contract(
+            Addr::unchecked( BOB.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
     
     let resp: AdminResponse = app
         .wrap()
@@ -158,7 +210,7 @@ fn exec_change_admin() {
 fn exec_change_threshold() {
     let mut app = App::default();
 
-    let code = ContractWrapper::new(execute, instantiate, query);
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
     let code_id = app.store_code(Box::new(code));
 
     let addr = app
@@ -166,10 +218,16 @@ fn exec_change_threshold() {
             code_id,
             Addr::unchecked( ALICE.to_string()),
             &InstantiateMsg {
-                admin: ALICE.to_string(),
-                signers: vec![ALICE.to_string(), CARL.to_string()],
-                threshold: 1,
+                admin: Some(ALICE.to_string()),
+                signers: vec![
+                    Signer { addr: ALICE.to_string(), weight: 1 },
+                    Signer { addr: CARL.to_string(), weight: 1 },
+                ],
+                threshold: Threshold::AbsoluteCount { weight: 1 },
                 whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
             },
             &[],
             "Contract",
@@ -178,7 +236,7 @@ fn exec_change_threshold() {
         .unwrap();
 
     let msg: ExecuteMsg<Empty> = ExecuteMsg::ChangeThreshold { 
-        new_threshold: 2,
+        new_threshold: Threshold::AbsoluteCount { weight: 2 },
     };
     let _ = app
         .execute_contract(
@@ -194,17 +252,48 @@ fn exec_change_threshold() {
         .unwrap();
     assert_eq!(
         resp,
-        ThresholdResponse {
-            threshold: 2
+        ThresholdResponse::AbsoluteCount {
+            weight: 2,
+            total_weight: 2,
         }
     );
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction {
+        msgs: vec![],
+        expires: None,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked( ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    // lowering the threshold passes the open transaction alice already signed
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ChangeThreshold { 
+        new_threshold: Threshold::AbsoluteCount { weight: 1 },
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked( ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let resp: TxExecutionsResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TxExecutions {})
+        .unwrap();
+    assert_eq!(resp.tx_executions[0].status, Some(TxStatus::Passed));
 }
 
 #[test]
 fn exec_change_whitelist_enabled() {
     let mut app = App::default();
 
-    let code = ContractWrapper::new(execute, instantiate, query);
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
     let code_id = app.store_code(Box::new(code));
 
     let addr = app
@@ -212,10 +301,16 @@ fn exec_change_whitelist_enabled() {
             code_id,
             Addr::unchecked( ALICE.to_string()),
             &InstantiateMsg {
-                admin: ALICE.to_string(),
-                signers: vec![ALICE.to_string(), CARL.to_string()],
-                threshold: 1,
+                admin: Some(ALICE.to_string()),
+                signers: vec![
+                    Signer { addr: ALICE.to_string(), weight: 1 },
+                    Signer { addr: CARL.to_string(), weight: 1 },
+                ],
+                threshold: Threshold::AbsoluteCount { weight: 1 },
                 whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
             },
             &[],
             "Contract",
@@ -250,7 +345,7 @@ fn exec_change_whitelist_enabled() {
 fn exec_add_signers() {
     let mut app = App::default();
 
-    let code = ContractWrapper::new(execute, instantiate, query);
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
     let code_id = app.store_code(Box::new(code));
 
     let addr = app
@@ -258,10 +353,13 @@ fn exec_add_signers() {
             code_id,
             Addr::unchecked("owner"),
             &InstantiateMsg {
-                admin: Addr::unchecked("owner").to_string(),
-                signers: vec![ALICE.to_string()],
-                threshold: 1,
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![Signer { addr: ALICE.to_string(), weight: 1 }],
+                threshold: Threshold::AbsoluteCount { weight: 1 },
                 whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
             },
             &[],
             "Contract",
@@ -276,12 +374,12 @@ fn exec_add_signers() {
     assert_eq!(
         resp,
         SignerListResponse {
-            signers: vec![ALICE.to_string()],
+            signers: vec![Signer { addr: ALICE.to_string(), weight: 1 }],
         }
     );
 
     let msg: ExecuteMsg<Empty> = ExecuteMsg::AddSigners { 
-        signers: vec![BOB.to_string()],
+        signers: vec![Signer { addr: BOB.to_string(), weight: 1 }],
     };
     let _ = app
         .execute_contract(
@@ -298,16 +396,71 @@ fn exec_add_signers() {
     assert_eq!(
         resp,
         SignerListResponse {
-            signers: vec![ALICE.to_string(), BOB.to_string()],
+            signers: vec![
+                Signer { addr: ALICE.to_string(), weight: 1 },
+                Signer { addr: BOB.to_string(), weight: 1 },
+            ],
         }
     );
+
+    // a zero weight signer is refused
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::AddSigners { 
+        signers: vec![Signer { addr: CARL.to_string(), weight: 0 }],
+    };
+    let err = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::ZeroWeight { signer: Addr::unchecked(CARL) },
+        err.downcast().unwrap()
+    );
+
+    // the summed weight has to fit, both when adding signers and when instantiating
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::AddSigners {
+        signers: vec![Signer { addr: CARL.to_string(), weight: u64::MAX }],
+    };
+    let err = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap_err();
+    assert_eq!(ContractError::WeightOverflow {}, err.downcast().unwrap());
+
+    let err = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![
+                    Signer { addr: ALICE.to_string(), weight: u64::MAX },
+                    Signer { addr: BOB.to_string(), weight: 1 },
+                ],
+                threshold: Threshold::AbsoluteCount { weight: 1 },
+                whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
+            },
+            &[],
+            "Contract",
+            None,
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::WeightOverflow {}, err.downcast().unwrap());
 }
 
 #[test]
 fn exec_remove_signers() {
     let mut app = App::default();
 
-    let code = ContractWrapper::new(execute, instantiate, query);
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
     let code_id = app.store_code(Box::new(code));
 
     let addr = app
@@ -315,10 +468,16 @@ fn exec_remove_signers() {
             code_id,
             Addr::unchecked("owner"),
             &InstantiateMsg {
-                admin: Addr::unchecked("owner").to_string(),
-                signers: vec![ALICE.to_string(), BOB.to_string()],
-                threshold: 1,
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![
+                    Signer { addr: ALICE.to_string(), weight: 1 },
+                    Signer { addr: BOB.to_string(), weight: 1 },
+                ],
+                threshold: Threshold::AbsoluteCount { weight: 1 },
                 whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
             },
             &[],
             "Contract",
@@ -333,7 +492,10 @@ fn exec_remove_signers() {
     assert_eq!(
         resp,
         SignerListResponse {
-            signers: vec![ALICE.to_string(), BOB.to_string()],
+            signers: vec![
+                Signer { addr: ALICE.to_string(), weight: 1 },
+                Signer { addr: BOB.to_string(), weight: 1 },
+            ],
         }
     );
 
@@ -355,7 +517,7 @@ fn exec_remove_signers() {
     assert_eq!(
         resp,
         SignerListResponse {
-            signers: vec![ALICE.to_string()],
+            signers: vec![Signer { addr: ALICE.to_string(), weight: 1 }],
         }
     );
 }
@@ -364,7 +526,7 @@ fn exec_remove_signers() {
 fn exec_execute_transaction() {
     let mut app = mock_app();
 
-    let code = ContractWrapper::new(execute, instantiate, query);
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
     let code_id = app.store_code(Box::new(code));
 
     let addr = app
@@ -372,10 +534,13 @@ fn exec_execute_transaction() {
             code_id,
             Addr::unchecked("owner"),
             &InstantiateMsg {
-                admin: Addr::unchecked("owner").to_string(),
-                signers: vec![Addr::unchecked("owner").to_string()],
-                threshold: 1,
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![Signer { addr: Addr::unchecked("owner").to_string(), weight: 1 }],
+                threshold: Threshold::AbsoluteCount { weight: 1 },
                 whitelist_enabled: true,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
             },
             &[],
             "Contract",
@@ -419,6 +584,7 @@ fn exec_execute_transaction() {
     ];
     let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction {
         msgs: messages,
+        expires: None,
     };
     let res = app
         .execute_contract(
@@ -429,6 +595,18 @@ fn exec_execute_transaction() {
         ).unwrap();
     assert_eq!(res.events[1].attributes, [("_contract_addr", "contract0"), ("action", "execute_transaction"), ("tx_id", "1")]);
     
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteApproved {
+        tx_id: 1,
+    };
+    let res = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+    assert_eq!(res.events[1].attributes, [("_contract_addr", "contract0"), ("action", "execute_approved"), ("tx_id", "1")]);
+
     let balance = app.wrap().query_balance(CARL.to_string(), DENOM).unwrap();
     assert_eq!(balance.amount, Uint128::new(1000));
     assert_eq!(balance.denom, DENOM);
@@ -441,13 +619,17 @@ fn exec_execute_transaction() {
         resp.tx_executions.len(),
         1
     );
+    assert_eq!(
+        resp.tx_executions[0].results,
+        vec![MsgResult { msg_index: 0, success: true, error: None }],
+    );
 }
 
 #[test]
 fn exec_sign_transaction() {
     let mut app = mock_app();
 
-    let code = ContractWrapper::new(execute, instantiate, query);
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
     let code_id = app.store_code(Box::new(code));
 
     let addr = app
@@ -455,10 +637,16 @@ fn exec_sign_transaction() {
             code_id,
             Addr::unchecked("owner"),
             &InstantiateMsg {
-                admin: Addr::unchecked("owner").to_string(),
-                signers: vec![Addr::unchecked("owner").to_string(), ALICE.to_string()],
-                threshold: 2,
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![
+                    Signer { addr: Addr::unchecked("owner").to_string(), weight: 1 },
+                    Signer { addr: ALICE.to_string(), weight: 1 },
+                ],
+                threshold: Threshold::AbsoluteCount { weight: 2 },
                 whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
             },
             &[],
             "Contract",
@@ -480,6 +668,7 @@ fn exec_sign_transaction() {
     ];
     let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction {
         msgs: messages,
+        expires: None,
     };
     let res = app
         .execute_contract(
@@ -511,6 +700,27 @@ fn exec_sign_transaction() {
         ).unwrap();
     assert_eq!(res.events[1].attributes, [("_contract_addr", "contract0"), ("action", "sign_transaction"), ("tx_id", "1")]);
 
+    let resp: TxExecutionsResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TxExecutions {})
+        .unwrap();
+    assert_eq!(
+        resp.tx_executions[0].status,
+        Some(TxStatus::Passed),
+    );
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteApproved {
+        tx_id: 1,
+    };
+    let res = app
+        .execute_contract(
+            Addr::unchecked(ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+    assert_eq!(res.events[1].attributes, [("_contract_addr", "contract0"), ("action", "execute_approved"), ("tx_id", "1")]);
+
     let balance = app.wrap().query_balance(CARL.to_string(), DENOM).unwrap();
     assert_eq!(balance.amount, Uint128::new(1000));
     assert_eq!(balance.denom, DENOM);
@@ -529,7 +739,7 @@ fn exec_sign_transaction() {
 fn exec_set_whitelist_addresses() {
     let mut app = App::default();
 
-    let code = ContractWrapper::new(execute, instantiate, query);
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
     let code_id = app.store_code(Box::new(code));
 
     let addr = app
@@ -537,10 +747,13 @@ fn exec_set_whitelist_addresses() {
             code_id,
             Addr::unchecked("owner"),
             &InstantiateMsg {
-                admin: Addr::unchecked("owner").to_string(),
-                signers: vec![ALICE.to_string()],
-                threshold: 1,
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![Signer { addr: ALICE.to_string(), weight: 1 }],
+                threshold: Threshold::AbsoluteCount { weight: 1 },
                 whitelist_enabled: true,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
             },
             &[],
             "Contract",
@@ -602,7 +815,7 @@ fn exec_set_whitelist_addresses() {
 fn exec_remove_whitelist_addresses() {
     let mut app = App::default();
 
-    let code = ContractWrapper::new(execute, instantiate, query);
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
     let code_id = app.store_code(Box::new(code));
 
     let addr = app
@@ -610,10 +823,13 @@ fn exec_remove_whitelist_addresses() {
             code_id,
             Addr::unchecked("owner"),
             &InstantiateMsg {
-                admin: Addr::unchecked("owner").to_string(),
-                signers: vec![ALICE.to_string()],
-                threshold: 1,
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![Signer { addr: ALICE.to_string(), weight: 1 }],
+                threshold: Threshold::AbsoluteCount { weight: 1 },
                 whitelist_enabled: true,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
             },
             &[],
             "Contract",
@@ -673,7 +889,7 @@ fn exec_remove_whitelist_addresses() {
 fn exec_set_transfer_limits() {
     let mut app = App::default();
 
-    let code = ContractWrapper::new(execute, instantiate, query);
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
     let code_id = app.store_code(Box::new(code));
 
     let addr = app
@@ -681,10 +897,13 @@ fn exec_set_transfer_limits() {
             code_id,
             Addr::unchecked("owner"),
             &InstantiateMsg {
-                admin: Addr::unchecked("owner").to_string(),
-                signers: vec![ALICE.to_string()],
-                threshold: 1,
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![Signer { addr: ALICE.to_string(), weight: 1 }],
+                threshold: Threshold::AbsoluteCount { weight: 1 },
                 whitelist_enabled: true,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
             },
             &[],
             "Contract",
@@ -787,7 +1006,7 @@ fn exec_set_transfer_limits() {
 fn exec_remove_transfer_limits() {
     let mut app = App::default();
 
-    let code = ContractWrapper::new(execute, instantiate, query);
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
     let code_id = app.store_code(Box::new(code));
 
     let addr = app
@@ -795,10 +1014,13 @@ fn exec_remove_transfer_limits() {
             code_id,
             Addr::unchecked("owner"),
             &InstantiateMsg {
-                admin: Addr::unchecked("owner").to_string(),
-                signers: vec![ALICE.to_string()],
-                threshold: 1,
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![Signer { addr: ALICE.to_string(), weight: 1 }],
+                threshold: Threshold::AbsoluteCount { weight: 1 },
                 whitelist_enabled: true,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
             },
             &[],
             "Contract",
@@ -871,3 +1093,3443 @@ fn exec_remove_transfer_limits() {
 
     assert_eq!(resp_coins, expected_coins);
 }
+
+#[test]
+fn exec_sign_transaction_weighted() {
+    let mut app = mock_app();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![
+                    Signer { addr: ALICE.to_string(), weight: 2 },
+                    Signer { addr: BOB.to_string(), weight: 1 },
+                    Signer { addr: CARL.to_string(), weight: 1 },
+                ],
+                threshold: Threshold::AbsoluteCount { weight: 3 },
+                whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
+            },
+            &[],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let _ = app.send_tokens(Addr::unchecked("owner"), addr.clone(), &[coin(10000, DENOM)]);
+
+    let messages = vec![
+        BankMsg::Send {
+            to_address: CARL.to_string(),
+            amount: vec![coin(1000, DENOM)],
+        }
+        .into(),
+    ];
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction {
+        msgs: messages,
+        expires: None,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(BOB.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    // bob and carl together only reach a weight of 2
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::SignTransaction {
+        tx_id: 1,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(CARL.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let resp: TxExecutionsResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TxExecutions {})
+        .unwrap();
+    assert_eq!(
+        resp.tx_executions[0].status,
+        Some(TxStatus::Pending),
+    );
+
+    // alice carries enough weight to complete the approval
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::SignTransaction {
+        tx_id: 1,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let resp: TxExecutionsResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TxExecutions {})
+        .unwrap();
+    assert_eq!(
+        resp.tx_executions[0].status,
+        Some(TxStatus::Passed),
+    );
+}
+
+#[test]
+fn exec_sign_transaction_expired() {
+    let mut app = mock_app();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![
+                    Signer { addr: Addr::unchecked("owner").to_string(), weight: 1 },
+                    Signer { addr: ALICE.to_string(), weight: 1 },
+                ],
+                threshold: Threshold::AbsoluteCount { weight: 2 },
+                whitelist_enabled: false,
+                default_expiry: Some(Duration::Time(3600)),
+                open_execution: false,
+                group: None,
+            },
+            &[],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let messages = vec![
+        BankMsg::Send {
+            to_address: CARL.to_string(),
+            amount: vec![coin(1000, DENOM)],
+        }
+        .into(),
+    ];
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction {
+        msgs: messages.clone(),
+        expires: None,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let expires_at_height = app.block_info().height + 5;
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction {
+        msgs: messages,
+        expires: Some(Expiration::AtHeight(expires_at_height)),
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let resp: TxExecutionsResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TxExecutions {})
+        .unwrap();
+    assert_eq!(
+        resp.tx_executions[0].expires,
+        Expiration::AtTime(app.block_info().time.plus_seconds(3600)),
+    );
+    assert_eq!(
+        resp.tx_executions[1].expires,
+        Expiration::AtHeight(expires_at_height),
+    );
+
+    app.update_block(|block| block.height += 5);
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::SignTransaction {
+        tx_id: 2,
+    };
+    let err = app
+        .execute_contract(
+            Addr::unchecked(ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::Expired { tx_id: 2 },
+        err.downcast().unwrap(),
+    );
+
+    let resp: TxExecutionsResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TxExecutions {})
+        .unwrap();
+    assert_eq!(
+        resp.tx_executions[0].status,
+        Some(TxStatus::Pending),
+    );
+    assert_eq!(
+        resp.tx_executions[1].status,
+        Some(TxStatus::Expired),
+    );
+}
+
+#[test]
+fn exec_reject_transaction() {
+    let mut app = mock_app();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![
+                    Signer { addr: Addr::unchecked("owner").to_string(), weight: 1 },
+                    Signer { addr: ALICE.to_string(), weight: 1 },
+                    Signer { addr: BOB.to_string(), weight: 1 },
+                ],
+                threshold: Threshold::AbsoluteCount { weight: 2 },
+                whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
+            },
+            &[],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let messages = vec![
+        BankMsg::Send {
+            to_address: CARL.to_string(),
+            amount: vec![coin(1000, DENOM)],
+        }
+        .into(),
+    ];
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction {
+        msgs: messages,
+        expires: None,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::RejectTransaction {
+        tx_id: 1,
+    };
+    let res = app
+        .execute_contract(
+            Addr::unchecked(ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+    assert_eq!(res.events[1].attributes, [("_contract_addr", "contract0"), ("action", "reject_transaction"), ("tx_id", "1")]);
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::AlreadyVoted { tx_id: 1, voter: Addr::unchecked(ALICE) },
+        err.downcast().unwrap(),
+    );
+
+    let resp: TxExecutionsResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TxExecutions {})
+        .unwrap();
+    assert_eq!(
+        resp.tx_executions[0].status,
+        Some(TxStatus::Pending),
+    );
+
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(BOB.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let resp: TxExecutionsResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TxExecutions {})
+        .unwrap();
+    assert_eq!(
+        resp.tx_executions[0].status,
+        Some(TxStatus::Rejected),
+    );
+    assert_eq!(
+        resp.tx_executions[0].rejections,
+        vec![Addr::unchecked(ALICE), Addr::unchecked(BOB)],
+    );
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::SignTransaction {
+        tx_id: 1,
+    };
+    let err = app
+        .execute_contract(
+            Addr::unchecked(BOB.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::InvalidStatus { tx_id: 1 },
+        err.downcast().unwrap(),
+    );
+}
+
+#[test]
+fn exec_cancel_transaction() {
+    let mut app = mock_app();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![
+                    Signer { addr: ALICE.to_string(), weight: 1 },
+                    Signer { addr: BOB.to_string(), weight: 1 },
+                ],
+                threshold: Threshold::AbsoluteCount { weight: 2 },
+                whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
+            },
+            &[],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let messages = vec![
+        BankMsg::Send {
+            to_address: CARL.to_string(),
+            amount: vec![coin(1000, DENOM)],
+        }
+        .into(),
+    ];
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction {
+        msgs: messages,
+        expires: None,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::CancelTransaction {
+        tx_id: 1,
+    };
+    let err = app
+        .execute_contract(
+            Addr::unchecked(BOB.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::MissingPermission {
+            sender: Addr::unchecked(BOB),
+            permission: Permission::CancelTransaction,
+        },
+        err.downcast().unwrap(),
+    );
+
+    let res = app
+        .execute_contract(
+            Addr::unchecked(ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+    assert_eq!(res.events[1].attributes, [("_contract_addr", "contract0"), ("action", "cancel_transaction"), ("tx_id", "1"), ("cancelled_by", ALICE)]);
+
+    let resp: TxExecutionsResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TxExecutions {})
+        .unwrap();
+    assert_eq!(
+        resp.tx_executions[0].status,
+        Some(TxStatus::Cancelled),
+    );
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::SignTransaction {
+        tx_id: 1,
+    };
+    let err = app
+        .execute_contract(
+            Addr::unchecked(BOB.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::InvalidStatus { tx_id: 1 },
+        err.downcast().unwrap(),
+    );
+}
+
+#[test]
+fn exec_execute_transaction_failed() {
+    let mut app = mock_app();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![Signer { addr: Addr::unchecked("owner").to_string(), weight: 1 }],
+                threshold: Threshold::AbsoluteCount { weight: 1 },
+                whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
+            },
+            &[],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let _ = app.send_tokens(Addr::unchecked("owner"), addr.clone(), &[coin(1000, DENOM)]);
+
+    // the second message overdraws the account and fails on its own
+    let messages = vec![
+        BankMsg::Send {
+            to_address: CARL.to_string(),
+            amount: vec![coin(1000, DENOM)],
+        }
+        .into(),
+        BankMsg::Send {
+            to_address: CARL.to_string(),
+            amount: vec![coin(1000, DENOM)],
+        }
+        .into(),
+    ];
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction {
+        msgs: messages,
+        expires: None,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteApproved {
+        tx_id: 1,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    // the messages are dispatched together, so the first send is reverted with the second
+    let balance = app.wrap().query_balance(CARL.to_string(), DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::zero());
+
+    let resp: TxExecutionsResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TxExecutions {})
+        .unwrap();
+    let tx = &resp.tx_executions[0];
+    assert_eq!(tx.status, Some(TxStatus::Failed));
+    assert!(tx.error.is_some());
+    assert_eq!(tx.results.len(), 2);
+    assert!(tx.results.iter().all(|result| !result.success));
+    assert_eq!(tx.results[1].msg_index, 1);
+}
+
+#[test]
+fn exec_failed_message_reverts_the_whole_transaction() {
+    let mut app = mock_app();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![Signer { addr: Addr::unchecked("owner").to_string(), weight: 1 }],
+                threshold: Threshold::AbsoluteCount { weight: 1 },
+                whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
+            },
+            &[],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let _ = app.send_tokens(Addr::unchecked("owner"), addr.clone(), &[coin(1000, DENOM)]);
+
+    // the first message overdraws the account, the second one would succeed on its own
+    let messages = vec![
+        BankMsg::Send {
+            to_address: CARL.to_string(),
+            amount: vec![coin(2000, DENOM)],
+        }
+        .into(),
+        BankMsg::Send {
+            to_address: BOB.to_string(),
+            amount: vec![coin(500, DENOM)],
+        }
+        .into(),
+    ];
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction {
+        msgs: messages,
+        expires: None,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteApproved {
+        tx_id: 1,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let balance = app.wrap().query_balance(BOB.to_string(), DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::zero());
+    let balance = app.wrap().query_balance(addr.to_string(), DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(1000));
+
+    let resp: TxExecutionsResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TxExecutions {})
+        .unwrap();
+    let tx = &resp.tx_executions[0];
+    assert_eq!(tx.status, Some(TxStatus::Failed));
+    assert!(tx.error.is_some());
+    assert!(tx.results.iter().all(|result| !result.success));
+
+    // only the account itself may dispatch the messages of a transaction
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::DispatchTransaction {
+        tx_id: 1,
+    };
+    let err = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized { sender: Addr::unchecked("owner") },
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn exec_execute_approved_open_execution() {
+    let mut app = mock_app();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![
+                    Signer { addr: ALICE.to_string(), weight: 1 },
+                    Signer { addr: BOB.to_string(), weight: 1 },
+                ],
+                threshold: Threshold::AbsoluteCount { weight: 2 },
+                whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: true,
+                group: None,
+            },
+            &[],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let _ = app.send_tokens(Addr::unchecked("owner"), addr.clone(), &[coin(10000, DENOM)]);
+
+    let messages = vec![
+        BankMsg::Send {
+            to_address: CARL.to_string(),
+            amount: vec![coin(1000, DENOM)],
+        }
+        .into(),
+    ];
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction {
+        msgs: messages,
+        expires: None,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteApproved {
+        tx_id: 1,
+    };
+    let err = app
+        .execute_contract(
+            Addr::unchecked(CARL.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::InvalidStatus { tx_id: 1 },
+        err.downcast().unwrap(),
+    );
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::SignTransaction {
+        tx_id: 1,
+    };
+    let res = app
+        .execute_contract(
+            Addr::unchecked(BOB.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    // approving only passes the transaction, nothing is dispatched yet
+    assert_eq!(res.events.len(), 2);
+    let balance = app.wrap().query_balance(CARL.to_string(), DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::zero());
+
+    // carl is not a signer, but open execution lets anyone dispatch
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteApproved {
+        tx_id: 1,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(CARL.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let balance = app.wrap().query_balance(CARL.to_string(), DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(1000));
+
+    let resp: TxExecutionsResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TxExecutions {})
+        .unwrap();
+    assert_eq!(
+        resp.tx_executions[0].status,
+        Some(TxStatus::Done),
+    );
+}
+
+#[test]
+fn exec_execute_approved_timelock() {
+    let mut app = mock_app();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![Signer { addr: ALICE.to_string(), weight: 1 }],
+                threshold: Threshold::AbsoluteCount { weight: 1 },
+                whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
+            },
+            &[],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::SetTimelock {
+        timelock: Some(Duration::Time(86400)),
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let resp: TimelockResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::Timelock {})
+        .unwrap();
+    assert_eq!(
+        resp,
+        TimelockResponse {
+            timelock: Some(Duration::Time(86400)),
+        }
+    );
+
+    let _ = app.send_tokens(Addr::unchecked("owner"), addr.clone(), &[coin(10000, DENOM)]);
+
+    let messages = vec![
+        BankMsg::Send {
+            to_address: CARL.to_string(),
+            amount: vec![coin(1000, DENOM)],
+        }
+        .into(),
+    ];
+    for _ in 0..2 {
+        let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction {
+            msgs: messages.clone(),
+            expires: None,
+        };
+        let _ = app
+            .execute_contract(
+                Addr::unchecked(ALICE.to_string()),
+                addr.clone(),
+                &msg,
+                &[],
+            ).unwrap();
+    }
+
+    let resp: TxExecutionsResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TxExecutions {})
+        .unwrap();
+    assert_eq!(resp.tx_executions[0].status, Some(TxStatus::Passed));
+    assert_eq!(
+        resp.tx_executions[0].executable_at,
+        Some(Expiration::AtTime(app.block_info().time.plus_seconds(86400))),
+    );
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteApproved {
+        tx_id: 1,
+    };
+    let err = app
+        .execute_contract(
+            Addr::unchecked(ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::Timelocked { tx_id: 1 },
+        err.downcast().unwrap(),
+    );
+
+    // the second transaction is still cancellable during the timelock
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::CancelTransaction {
+        tx_id: 2,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    app.update_block(|block| block.time = block.time.plus_seconds(86400));
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteApproved {
+        tx_id: 1,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteApproved {
+        tx_id: 2,
+    };
+    let err = app
+        .execute_contract(
+            Addr::unchecked(ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::InvalidStatus { tx_id: 2 },
+        err.downcast().unwrap(),
+    );
+
+    let balance = app.wrap().query_balance(CARL.to_string(), DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(1000));
+
+    let resp: TxExecutionsResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TxExecutions {})
+        .unwrap();
+    assert_eq!(resp.tx_executions[0].status, Some(TxStatus::Done));
+    assert_eq!(resp.tx_executions[1].status, Some(TxStatus::Cancelled));
+}
+
+#[test]
+fn exec_sign_transaction_duplicate_and_unsign() {
+    let mut app = mock_app();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![
+                    Signer { addr: ALICE.to_string(), weight: 1 },
+                    Signer { addr: BOB.to_string(), weight: 1 },
+                    Signer { addr: CARL.to_string(), weight: 1 },
+                ],
+                threshold: Threshold::AbsoluteCount { weight: 3 },
+                whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
+            },
+            &[],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let messages = vec![
+        BankMsg::Send {
+            to_address: CARL.to_string(),
+            amount: vec![coin(1000, DENOM)],
+        }
+        .into(),
+    ];
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction {
+        msgs: messages,
+        expires: None,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::SignTransaction {
+        tx_id: 1,
+    };
+    let err = app
+        .execute_contract(
+            Addr::unchecked(ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::AlreadySigned { tx_id: 1, signer: Addr::unchecked(ALICE) },
+        err.downcast().unwrap(),
+    );
+
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(BOB.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::UnsignTransaction {
+        tx_id: 1,
+    };
+    let res = app
+        .execute_contract(
+            Addr::unchecked(BOB.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+    assert_eq!(res.events[1].attributes, [("_contract_addr", "contract0"), ("action", "unsign_transaction"), ("tx_id", "1")]);
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(BOB.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::NotSigned { tx_id: 1, signer: Addr::unchecked(BOB) },
+        err.downcast().unwrap(),
+    );
+
+    let resp: TxExecutionsResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TxExecutions {})
+        .unwrap();
+    assert_eq!(resp.tx_executions[0].signers, vec![Addr::unchecked(ALICE)]);
+    assert_eq!(resp.tx_executions[0].status, Some(TxStatus::Pending));
+}
+
+#[test]
+fn exec_sign_transaction_policy_changed() {
+    let mut app = mock_app();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![
+                    Signer { addr: ALICE.to_string(), weight: 1 },
+                    Signer { addr: BOB.to_string(), weight: 1 },
+                ],
+                threshold: Threshold::AbsoluteCount { weight: 2 },
+                whitelist_enabled: true,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
+            },
+            &[],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::SetWhitelistAddresses { 
+        addresses: vec![CARL.to_string()],
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let messages = vec![
+        BankMsg::Send {
+            to_address: CARL.to_string(),
+            amount: vec![coin(1000, DENOM)],
+        }
+        .into(),
+    ];
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction {
+        msgs: messages,
+        expires: None,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::RemoveWhitelistAddresses { 
+        addresses: vec![CARL.to_string()],
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::SignTransaction {
+        tx_id: 1,
+    };
+    let res = app
+        .execute_contract(
+            Addr::unchecked(BOB.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+    assert_eq!(
+        res.events[1].attributes,
+        [
+            ("_contract_addr", "contract0"),
+            ("action", "sign_transaction"),
+            ("tx_id", "1"),
+            ("status", "failed"),
+            ("error", "carl is not whitelisted"),
+        ],
+    );
+
+    let resp: TxExecutionsResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TxExecutions {})
+        .unwrap();
+    assert_eq!(resp.tx_executions[0].status, Some(TxStatus::Failed));
+    assert_eq!(resp.tx_executions[0].error, Some("carl is not whitelisted".to_string()));
+}
+
+#[test]
+fn exec_remove_signers_updates_open_transactions() {
+    let mut app = mock_app();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![
+                    Signer { addr: ALICE.to_string(), weight: 1 },
+                    Signer { addr: BOB.to_string(), weight: 1 },
+                    Signer { addr: CARL.to_string(), weight: 1 },
+                ],
+                threshold: Threshold::AbsoluteCount { weight: 2 },
+                whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
+            },
+            &[],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let messages = vec![
+        BankMsg::Send {
+            to_address: CARL.to_string(),
+            amount: vec![coin(1000, DENOM)],
+        }
+        .into(),
+    ];
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction {
+        msgs: messages.clone(),
+        expires: None,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::SignTransaction {
+        tx_id: 1,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(BOB.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    // carl rejects a second transaction, which bob could still carry
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction {
+        msgs: messages,
+        expires: None,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::RejectTransaction {
+        tx_id: 2,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(CARL.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let resp: TxExecutionsResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TxExecutions {})
+        .unwrap();
+    assert_eq!(resp.tx_executions[0].status, Some(TxStatus::Passed));
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::RemoveSigners { 
+        signers: vec![BOB.to_string()],
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    // bob's approval no longer counts once bob is removed
+    let resp: TxExecutionsResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TxExecutions {})
+        .unwrap();
+    assert_eq!(resp.tx_executions[0].status, Some(TxStatus::Pending));
+    // without bob the second transaction can no longer reach the threshold
+    assert_eq!(resp.tx_executions[1].status, Some(TxStatus::Rejected));
+
+    // executing the transaction sends it back to voting instead of dispatching it
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteApproved {
+        tx_id: 1,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+    assert_eq!(app.wrap().query_balance(CARL, DENOM).unwrap().amount, Uint128::zero());
+    let err = app
+        .execute_contract(
+            Addr::unchecked(ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::InvalidStatus { tx_id: 1 },
+        err.downcast().unwrap(),
+    );
+}
+
+#[test]
+fn exec_percentage_threshold_follows_signers() {
+    let mut app = mock_app();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![
+                    Signer { addr: Addr::unchecked("owner").to_string(), weight: 1 },
+                    Signer { addr: ALICE.to_string(), weight: 1 },
+                    Signer { addr: BOB.to_string(), weight: 1 },
+                    Signer { addr: CARL.to_string(), weight: 1 },
+                ],
+                threshold: Threshold::AbsolutePercentage { percentage: Decimal::percent(50) },
+                whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
+            },
+            &[],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let resp: ThresholdResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::Threshold {})
+        .unwrap();
+    assert_eq!(
+        resp,
+        ThresholdResponse::AbsolutePercentage {
+            percentage: Decimal::percent(50),
+            total_weight: 4,
+        }
+    );
+
+    let messages = vec![
+        BankMsg::Send {
+            to_address: CARL.to_string(),
+            amount: vec![coin(1000, DENOM)],
+        }
+        .into(),
+    ];
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction {
+        msgs: messages.clone(),
+        expires: None,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::RemoveSigners { 
+        signers: vec![BOB.to_string(), CARL.to_string()],
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    // half of the two remaining signers is a single approval
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction {
+        msgs: messages,
+        expires: None,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let resp: TxExecutionsResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TxExecutions {})
+        .unwrap();
+    // the removal already carried the first transaction over the threshold
+    assert_eq!(resp.tx_executions[0].status, Some(TxStatus::Passed));
+    assert_eq!(resp.tx_executions[1].status, Some(TxStatus::Passed));
+
+    // a percentage of nothing is nothing, so the last signers can not be removed
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::RemoveSigners { 
+        signers: vec!["owner".to_string(), ALICE.to_string()],
+    };
+    let err = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap_err();
+    assert_eq!(ContractError::NoSigners {}, err.downcast().unwrap());
+}
+
+#[test]
+fn exec_grant_and_revoke_role() {
+    let mut app = App::default();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![Signer { addr: ALICE.to_string(), weight: 1 }],
+                threshold: Threshold::AbsoluteCount { weight: 1 },
+                whitelist_enabled: true,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
+            },
+            &[],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::GrantRole {
+        address: CARL.to_string(),
+        role: Role::PolicyManager,
+    };
+    let err = app
+        .execute_contract(
+            Addr::unchecked(CARL.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized { sender: Addr::unchecked(CARL) },
+        err.downcast().unwrap(),
+    );
+
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let resp: RolesResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::Roles {})
+        .unwrap();
+    assert_eq!(
+        resp,
+        RolesResponse {
+            roles: vec![RoleHolder {
+                address: CARL.to_string(),
+                roles: vec![Role::PolicyManager],
+            }],
+        }
+    );
+
+    // a policy manager can edit the whitelist but not the signers
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::SetWhitelistAddresses { 
+        addresses: vec![BOB.to_string()],
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(CARL.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let resp: WhitelistAddressesResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::WhitelistAddresses {})
+        .unwrap();
+    assert_eq!(resp.whitelist_addresses, vec![BOB.to_string()]);
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::AddSigners {
+        signers: vec![Signer { addr: CARL.to_string(), weight: 1 }],
+    };
+    let err = app
+        .execute_contract(
+            Addr::unchecked(CARL.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::MissingPermission {
+            sender: Addr::unchecked(CARL),
+            permission: Permission::ManageSigners,
+        },
+        err.downcast().unwrap(),
+    );
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::RevokeRole {
+        address: CARL.to_string(),
+        role: Role::PolicyManager,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let resp: RolesResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::Roles {})
+        .unwrap();
+    assert_eq!(resp, RolesResponse { roles: vec![] });
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::SetWhitelistAddresses { 
+        addresses: vec![CARL.to_string()],
+    };
+    let err = app
+        .execute_contract(
+            Addr::unchecked(CARL.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::MissingPermission {
+            sender: Addr::unchecked(CARL),
+            permission: Permission::ManagePolicy,
+        },
+        err.downcast().unwrap(),
+    );
+}
+
+#[test]
+fn exec_change_admin_cancel_and_expire() {
+    let mut app = App::default();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked( ALICE.to_string()),
+            &InstantiateMsg {
+                admin: Some(ALICE.to_string()),
+                signers: vec![Signer { addr: CARL.to_string(), weight: 1 }],
+                threshold: Threshold::AbsoluteCount { weight: 1 },
+                whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
+            },
+            &[],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ChangeAdmin { 
+        new_admin: BOB.to_string(),
+        expires: None,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked( ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::CancelAdminTransfer {};
+    let _ = app
+        .execute_contract(
+            Addr::unchecked( ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let resp: PendingAdminResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::PendingAdmin {})
+        .unwrap();
+    assert_eq!(
+        resp,
+        PendingAdminResponse {
+            pending_admin: None,
+            expires: None,
+        }
+    );
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::AcceptAdmin {};
+    let err = app
+        .execute_contract(
+            Addr::unchecked( BOB.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::NoPendingAdmin {},
+        err.downcast().unwrap(),
+    );
+
+    let expires_at_height = app.block_info().height + 10;
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ChangeAdmin { 
+        new_admin: BOB.to_string(),
+        expires: Some(Expiration::AtHeight(expires_at_height)),
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked( ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    app.update_block(|block| block.height += 10);
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::AcceptAdmin {};
+    let err = app
+        .execute_contract(
+            Addr::unchecked( BOB.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::PendingAdminExpired {},
+        err.downcast().unwrap(),
+    );
+
+    let resp: AdminResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::Admin {})
+        .unwrap();
+    assert_eq!(
+        resp,
+        AdminResponse {
+            admin: ALICE.to_string(),
+        }
+    );
+}
+
+#[test]
+fn exec_self_governed_change_threshold() {
+    let mut app = App::default();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: None,
+                signers: vec![
+                    Signer { addr: ALICE.to_string(), weight: 1 },
+                    Signer { addr: BOB.to_string(), weight: 1 },
+                ],
+                threshold: Threshold::AbsoluteCount { weight: 2 },
+                whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
+            },
+            &[],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let resp: AdminResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::Admin {})
+        .unwrap();
+    assert_eq!(
+        resp,
+        AdminResponse {
+            admin: addr.to_string(),
+        }
+    );
+
+    // signers can only change the configuration through an approved transaction
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ChangeThreshold {
+        new_threshold: Threshold::AbsoluteCount { weight: 1 },
+    };
+    let err = app
+        .execute_contract(
+            Addr::unchecked(ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::MissingPermission {
+            sender: Addr::unchecked(ALICE),
+            permission: Permission::ManageSigners,
+        },
+        err.downcast().unwrap(),
+    );
+
+    let messages = vec![
+        WasmMsg::Execute {
+            contract_addr: addr.to_string(),
+            msg: to_json_binary(&msg).unwrap(),
+            funds: vec![],
+        }
+        .into(),
+    ];
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction {
+        msgs: messages,
+        expires: None,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(ALICE.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::SignTransaction {
+        tx_id: 1,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(BOB.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteApproved {
+        tx_id: 1,
+    };
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(BOB.to_string()),
+            addr.clone(),
+            &msg,
+            &[],
+        ).unwrap();
+
+    let resp: ThresholdResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::Threshold {})
+        .unwrap();
+    assert_eq!(
+        resp,
+        ThresholdResponse::AbsoluteCount {
+            weight: 1,
+            total_weight: 2,
+        }
+    );
+
+    let resp: TxExecutionsResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TxExecutions {})
+        .unwrap();
+    assert_eq!(resp.tx_executions[0].status, Some(TxStatus::Done));
+
+    // the account can not give up the calls it governs itself with
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::SetAllowedMsgTypes {
+        msg_types: vec![MsgType::BankSend],
+    };
+    let err = app
+        .execute_contract(addr.clone(), addr.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::SelfGovernanceLocked {}, err.downcast().unwrap());
+    let resp: AllowedMsgTypesResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::AllowedMsgTypes {})
+        .unwrap();
+    assert_eq!(resp.msg_types, MsgType::defaults());
+}
+
+#[test]
+fn exec_guardian_recovery_veto_and_execute() {
+    let mut app = App::default();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![
+                    Signer { addr: ALICE.to_string(), weight: 1 },
+                    Signer { addr: BOB.to_string(), weight: 1 },
+                ],
+                threshold: Threshold::AbsoluteCount { weight: 2 },
+                whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
+            },
+            &[],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let proposal: ExecuteMsg<Empty> = ExecuteMsg::ProposeRecovery {
+        signers: vec![Signer { addr: CARL.to_string(), weight: 1 }],
+        threshold: Threshold::AbsoluteCount { weight: 1 },
+    };
+    let err = app
+        .execute_contract(
+            Addr::unchecked("guardian1"),
+            addr.clone(),
+            &proposal,
+            &[],
+        ).unwrap_err();
+    assert_eq!(ContractError::RecoveryDisabled {}, err.downcast().unwrap());
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::SetRecoveryConfig {
+        config: Some(RecoveryConfig {
+            threshold: 0,
+            delay: Duration::Height(10),
+        }),
+    };
+    let err = app
+        .execute_contract(Addr::unchecked("owner"), addr.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidRecoveryThreshold {}, err.downcast().unwrap());
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::SetRecoveryConfig {
+        config: Some(RecoveryConfig {
+            threshold: 2,
+            delay: Duration::Height(10),
+        }),
+    };
+    let _ = app
+        .execute_contract(Addr::unchecked("owner"), addr.clone(), &msg, &[])
+        .unwrap();
+    for guardian in ["guardian1", "guardian2", "guardian3"] {
+        let msg: ExecuteMsg<Empty> = ExecuteMsg::GrantRole {
+            address: guardian.to_string(),
+            role: Role::Guardian,
+        };
+        let _ = app
+            .execute_contract(Addr::unchecked("owner"), addr.clone(), &msg, &[])
+            .unwrap();
+    }
+
+    // only guardians can start a recovery
+    let err = app
+        .execute_contract(
+            Addr::unchecked(ALICE),
+            addr.clone(),
+            &proposal,
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::NotGuardian { sender: Addr::unchecked(ALICE) },
+        err.downcast().unwrap(),
+    );
+
+    let _ = app
+        .execute_contract(Addr::unchecked("guardian1"), addr.clone(), &proposal, &[])
+        .unwrap();
+    let err = app
+        .execute_contract(
+            Addr::unchecked("guardian1"),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::ApproveRecovery {},
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::RecoveryAlreadyApproved { guardian: Addr::unchecked("guardian1") },
+        err.downcast().unwrap(),
+    );
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("guardian2"),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::ApproveRecovery {},
+            &[],
+        ).unwrap();
+
+    let resp: RecoveryResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::Recovery {})
+        .unwrap();
+    let recovery = resp.recovery.unwrap();
+    assert_eq!(recovery.approvals, vec![Addr::unchecked("guardian1"), Addr::unchecked("guardian2")]);
+    assert_eq!(recovery.executable_at, Some(Expiration::AtHeight(app.block_info().height + 10)));
+
+    // the recovery can not run before its delay
+    let err = app
+        .execute_contract(
+            Addr::unchecked("guardian1"),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::ExecuteRecovery {},
+            &[],
+        ).unwrap_err();
+    assert_eq!(ContractError::RecoveryNotReady {}, err.downcast().unwrap());
+
+    // a current signer vetoes the recovery
+    let res = app
+        .execute_contract(
+            Addr::unchecked(BOB),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::VetoRecovery {},
+            &[],
+        ).unwrap();
+    assert_eq!(res.events[1].attributes[1].value, "veto_recovery");
+    let resp: RecoveryResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::Recovery {})
+        .unwrap();
+    assert_eq!(resp.recovery, None);
+
+    // propose again, the approval of a revoked guardian stops counting
+    let _ = app
+        .execute_contract(Addr::unchecked("guardian2"), addr.clone(), &proposal, &[])
+        .unwrap();
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("guardian1"),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::ApproveRecovery {},
+            &[],
+        ).unwrap();
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::RevokeRole {
+        address: "guardian1".to_string(),
+        role: Role::Guardian,
+    };
+    let _ = app
+        .execute_contract(Addr::unchecked("owner"), addr.clone(), &msg, &[])
+        .unwrap();
+
+    let resp: RecoveryResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::Recovery {})
+        .unwrap();
+    let recovery = resp.recovery.unwrap();
+    assert_eq!(recovery.approvals, vec![Addr::unchecked("guardian2")]);
+    assert_eq!(recovery.executable_at, None);
+
+    app.update_block(|block| block.height += 10);
+    let err = app
+        .execute_contract(
+            Addr::unchecked("guardian2"),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::ExecuteRecovery {},
+            &[],
+        ).unwrap_err();
+    assert_eq!(ContractError::RecoveryNotReady {}, err.downcast().unwrap());
+
+    // another guardian approves and the delay passes
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("guardian3"),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::ApproveRecovery {},
+            &[],
+        ).unwrap();
+    app.update_block(|block| block.height += 10);
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("guardian3"),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::ExecuteRecovery {},
+            &[],
+        ).unwrap();
+
+    let resp: SignerListResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::Signerlist {})
+        .unwrap();
+    assert_eq!(resp.signers, vec![Signer { addr: CARL.to_string(), weight: 1 }]);
+    let resp: ThresholdResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::Threshold {})
+        .unwrap();
+    assert_eq!(resp, ThresholdResponse::AbsoluteCount { weight: 1, total_weight: 1 });
+    let resp: RecoveryResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::Recovery {})
+        .unwrap();
+    assert_eq!(resp.recovery, None);
+
+    // disabling recovery drops the recovery in progress
+    let proposal: ExecuteMsg<Empty> = ExecuteMsg::ProposeRecovery {
+        signers: vec![Signer { addr: BOB.to_string(), weight: 1 }],
+        threshold: Threshold::AbsoluteCount { weight: 1 },
+    };
+    let _ = app
+        .execute_contract(Addr::unchecked("guardian2"), addr.clone(), &proposal, &[])
+        .unwrap();
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::SetRecoveryConfig { config: None };
+    let _ = app
+        .execute_contract(Addr::unchecked("owner"), addr.clone(), &msg, &[])
+        .unwrap();
+    let resp: RecoveryResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::Recovery {})
+        .unwrap();
+    assert_eq!(resp.recovery, None);
+}
+
+#[test]
+fn exec_pause_and_unpause() {
+    let mut app = mock_app();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![
+                    Signer { addr: ALICE.to_string(), weight: 1 },
+                    Signer { addr: BOB.to_string(), weight: 1 },
+                ],
+                threshold: Threshold::AbsoluteCount { weight: 2 },
+                whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
+            },
+            &[coin(1000, DENOM)],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction {
+        msgs: vec![BankMsg::Send {
+            to_address: CARL.to_string(),
+            amount: vec![coin(100, DENOM)],
+        }.into()],
+        expires: None,
+    };
+    let _ = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &msg, &[])
+        .unwrap();
+
+    // a single signer freezes the account
+    let _ = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &ExecuteMsg::<Empty>::Pause {}, &[])
+        .unwrap();
+    let resp: PauseStatusResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::PauseStatus {})
+        .unwrap();
+    assert!(resp.paused);
+    let pause = resp.pause.unwrap();
+    assert_eq!(pause.paused_by, Addr::unchecked(ALICE));
+    assert_eq!(pause.paused_at_height, app.block_info().height);
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(BOB),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::SignTransaction { tx_id: 1 },
+            &[],
+        ).unwrap_err();
+    assert_eq!(ContractError::Paused {}, err.downcast().unwrap());
+    let err = app
+        .execute_contract(Addr::unchecked(BOB), addr.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::Paused {}, err.downcast().unwrap());
+
+    // unpausing by the signers needs the full threshold
+    let res = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &ExecuteMsg::<Empty>::Unpause {}, &[])
+        .unwrap();
+    assert_eq!(res.events[1].attributes[3].value, "true");
+    let err = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &ExecuteMsg::<Empty>::Unpause {}, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::AlreadyVotedUnpause { voter: Addr::unchecked(ALICE) },
+        err.downcast().unwrap(),
+    );
+    let _ = app
+        .execute_contract(Addr::unchecked(BOB), addr.clone(), &ExecuteMsg::<Empty>::Unpause {}, &[])
+        .unwrap();
+    let resp: PauseStatusResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::PauseStatus {})
+        .unwrap();
+    assert_eq!(resp, PauseStatusResponse { paused: false, pause: None });
+
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(BOB),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::SignTransaction { tx_id: 1 },
+            &[],
+        ).unwrap();
+
+    // the admin lifts a pause at once
+    let _ = app
+        .execute_contract(Addr::unchecked(BOB), addr.clone(), &ExecuteMsg::<Empty>::Pause {}, &[])
+        .unwrap();
+    let err = app
+        .execute_contract(
+            Addr::unchecked(ALICE),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::ExecuteApproved { tx_id: 1 },
+            &[],
+        ).unwrap_err();
+    assert_eq!(ContractError::Paused {}, err.downcast().unwrap());
+    let _ = app
+        .execute_contract(Addr::unchecked("owner"), addr.clone(), &ExecuteMsg::<Empty>::Unpause {}, &[])
+        .unwrap();
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(ALICE),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::ExecuteApproved { tx_id: 1 },
+            &[],
+        ).unwrap();
+    assert_eq!(app.wrap().query_balance(CARL, DENOM).unwrap(), coin(100, DENOM));
+}
+
+#[test]
+fn migrate_from_v0_1_0() {
+    let mut deps = mock_dependencies();
+    cw2::set_contract_version(deps.as_mut().storage, "crates.io:cw-account-management", "0.1.0").unwrap();
+    v0_1::STATE.save(deps.as_mut().storage, &v0_1::State {
+        admin: Addr::unchecked("owner"),
+        signers: vec![Addr::unchecked(ALICE), Addr::unchecked(BOB)],
+        threshold: 2,
+    }).unwrap();
+    v0_1::POLICY.save(deps.as_mut().storage, &v0_1::Policy {
+        whitelist_enabled: true,
+        whitelist_addresses: vec![Addr::unchecked(CARL)],
+        transfer_limits: vec![coin(100, DENOM)],
+    }).unwrap();
+    let send: CosmosMsg = BankMsg::Send {
+        to_address: CARL.to_string(),
+        amount: vec![coin(10, DENOM)],
+    }.into();
+    v0_1::TX_EXECUTION.save(deps.as_mut().storage, 1, &v0_1::TxData {
+        id: 1,
+        msgs: vec![send.clone()],
+        signers: vec![Addr::unchecked(ALICE), Addr::unchecked(BOB)],
+        status: Some(TxStatus::Done),
+    }).unwrap();
+    v0_1::TX_EXECUTION.save(deps.as_mut().storage, 2, &v0_1::TxData {
+        id: 2,
+        msgs: vec![send],
+        signers: vec![Addr::unchecked(BOB), Addr::unchecked(BOB)],
+        status: Some(TxStatus::Pending),
+    }).unwrap();
+    TX_NEXT_ID.save(deps.as_mut().storage, &3).unwrap();
+
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    assert_eq!(res.attributes[1].value, "0.1.0");
+    assert_eq!(
+        cw2::get_contract_version(deps.as_ref().storage).unwrap().version,
+        env!("CARGO_PKG_VERSION"),
+    );
+
+    let resp: SignerListResponse = from_json(
+        query(deps.as_ref(), mock_env(), QueryMsg::Signerlist {}).unwrap()
+    ).unwrap();
+    assert_eq!(resp.signers, vec![
+        Signer { addr: ALICE.to_string(), weight: 1 },
+        Signer { addr: BOB.to_string(), weight: 1 },
+    ]);
+    let resp: ThresholdResponse = from_json(
+        query(deps.as_ref(), mock_env(), QueryMsg::Threshold {}).unwrap()
+    ).unwrap();
+    assert_eq!(resp, ThresholdResponse::AbsoluteCount { weight: 2, total_weight: 2 });
+    let resp: TimelockResponse = from_json(
+        query(deps.as_ref(), mock_env(), QueryMsg::Timelock {}).unwrap()
+    ).unwrap();
+    assert_eq!(resp.timelock, None);
+
+    let resp: TxExecutionsResponse = from_json(
+        query(deps.as_ref(), mock_env(), QueryMsg::TxExecutions {}).unwrap()
+    ).unwrap();
+    assert_eq!(resp.tx_executions.len(), 2);
+    assert_eq!(resp.tx_executions[0].status, Some(TxStatus::Done));
+    assert_eq!(resp.tx_executions[1].status, Some(TxStatus::Pending));
+    assert_eq!(resp.tx_executions[1].proposer, Addr::unchecked(BOB));
+    // the duplicated signature of bob only counts once
+    assert_eq!(resp.tx_executions[1].signers, vec![Addr::unchecked(BOB)]);
+
+    // the migrated proposal can still be approved by the remaining signer
+    let info = mock_info(ALICE, &[]);
+    let _ = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::SignTransaction { tx_id: 2 }).unwrap();
+    let resp: TxExecutionsResponse = from_json(
+        query(deps.as_ref(), mock_env(), QueryMsg::TxExecutions {}).unwrap()
+    ).unwrap();
+    assert_eq!(resp.tx_executions[1].status, Some(TxStatus::Passed));
+}
+
+#[test]
+fn migrate_refuses_downgrade_and_other_contracts() {
+    let mut deps = mock_dependencies();
+    cw2::set_contract_version(deps.as_mut().storage, "crates.io:cw-account-management", "9.0.0").unwrap();
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+    assert_eq!(err, ContractError::CannotMigrateVersion { previous_version: "9.0.0".to_string() });
+
+    cw2::set_contract_version(deps.as_mut().storage, "crates.io:cw20-base", "0.1.0").unwrap();
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+    assert_eq!(err, ContractError::CannotMigrate { previous_contract: "crates.io:cw20-base".to_string() });
+}
+
+#[test]
+fn cw3_propose_vote_execute_and_close() {
+    let mut app = mock_app();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![
+                    Signer { addr: ALICE.to_string(), weight: 1 },
+                    Signer { addr: BOB.to_string(), weight: 2 },
+                    Signer { addr: CARL.to_string(), weight: 1 },
+                ],
+                threshold: Threshold::AbsoluteCount { weight: 3 },
+                whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
+            },
+            &[coin(1000, DENOM)],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let msgs: Vec<CosmosMsg> = vec![BankMsg::Send {
+        to_address: "owner".to_string(),
+        amount: vec![coin(100, DENOM)],
+    }.into()];
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::Propose {
+        title: "Pay owner".to_string(),
+        description: "Monthly payment".to_string(),
+        msgs: msgs.clone(),
+        latest: None,
+    };
+    let _ = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &msg, &[])
+        .unwrap();
+
+    let resp: ProposalResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::Proposal { proposal_id: 1 })
+        .unwrap();
+    assert_eq!(
+        resp,
+        ProposalResponse {
+            id: 1,
+            title: "Pay owner".to_string(),
+            description: "Monthly payment".to_string(),
+            msgs,
+            status: Status::Open,
+            expires: Expiration::Never {},
+            threshold: ThresholdResponse::AbsoluteCount { weight: 3, total_weight: 4 },
+        }
+    );
+
+    // an abstention does not support the proposal, a veto counts as a no
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(CARL),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::Vote { proposal_id: 1, vote: Vote::Abstain },
+            &[],
+        ).unwrap();
+    let err = app
+        .execute_contract(
+            Addr::unchecked(CARL),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::Vote { proposal_id: 1, vote: Vote::Yes },
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::AlreadyVoted { tx_id: 1, voter: Addr::unchecked(CARL) },
+        err.downcast().unwrap(),
+    );
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(BOB),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::Vote { proposal_id: 1, vote: Vote::Yes },
+            &[],
+        ).unwrap();
+
+    let resp: VoteListResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::ListVotes { proposal_id: 1, start_after: None, limit: None })
+        .unwrap();
+    assert_eq!(
+        resp.votes,
+        vec![
+            VoteInfo { proposal_id: 1, voter: ALICE.to_string(), vote: Vote::Yes, weight: 1 },
+            VoteInfo { proposal_id: 1, voter: BOB.to_string(), vote: Vote::Yes, weight: 2 },
+            VoteInfo { proposal_id: 1, voter: CARL.to_string(), vote: Vote::Abstain, weight: 1 },
+        ]
+    );
+
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(CARL),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::Execute { proposal_id: 1 },
+            &[],
+        ).unwrap();
+    assert_eq!(app.wrap().query_balance("owner", DENOM).unwrap(), coin(99100, DENOM));
+
+    // a proposal that runs out of time is closed by anyone
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::Propose {
+        title: "Expiring".to_string(),
+        description: String::new(),
+        msgs: vec![],
+        latest: Some(Expiration::AtHeight(app.block_info().height + 5)),
+    };
+    let _ = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &msg, &[])
+        .unwrap();
+    let err = app
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::Close { proposal_id: 2 },
+            &[],
+        ).unwrap_err();
+    assert_eq!(ContractError::NotExpired { tx_id: 2 }, err.downcast().unwrap());
+    app.update_block(|block| block.height += 5);
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::Close { proposal_id: 2 },
+            &[],
+        ).unwrap();
+
+    let resp: ProposalListResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::ReverseProposals { start_before: None, limit: None })
+        .unwrap();
+    let statuses: Vec<(u64, Status)> = resp.proposals.iter().map(|p| (p.id, p.status)).collect();
+    assert_eq!(statuses, vec![(2, Status::Rejected), (1, Status::Executed)]);
+    let resp: ProposalListResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::ListProposals { start_after: Some(1), limit: Some(1) })
+        .unwrap();
+    assert_eq!(resp.proposals.len(), 1);
+    assert_eq!(resp.proposals[0].id, 2);
+
+    let resp: VoterResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::Voter { address: BOB.to_string() })
+        .unwrap();
+    assert_eq!(resp.weight, Some(2));
+    let resp: VoterListResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::ListVoters { start_after: Some(ALICE.to_string()), limit: None })
+        .unwrap();
+    assert_eq!(
+        resp.voters,
+        vec![
+            VoterDetail { addr: BOB.to_string(), weight: 2 },
+            VoterDetail { addr: CARL.to_string(), weight: 1 },
+        ]
+    );
+}
+
+// minimal cw4 group that notifies its hooks whenever its members change
+mod mock_group {
+    use cosmwasm_std::{
+        to_json_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Order, Response, StdError,
+        StdResult, WasmMsg,
+    };
+    use cw_storage_plus::{Item, Map};
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+
+    use crate::cw4::{
+        Cw4QueryMsg, Member, MemberChangedHookMsg, MemberDiff, MemberListResponse, MemberResponse, TotalWeightResponse,
+    };
+    use crate::msg::ExecuteMsg as AccountExecuteMsg;
+
+    const MEMBERS: Map<&str, u64> = Map::new("members");
+    const HOOKS: Item<Vec<String>> = Item::new("hooks");
+    const ADMIN: Item<String> = Item::new("admin");
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+    pub struct InstantiateMsg {
+        pub admin: String,
+        pub members: Vec<Member>,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub enum ExecuteMsg {
+        UpdateMembers { add: Vec<Member>, remove: Vec<String> },
+        AddHook { addr: String },
+        RemoveHook { addr: String },
+    }
+
+    pub fn instantiate(deps: DepsMut, _env: Env, _info: MessageInfo, msg: InstantiateMsg) -> StdResult<Response> {
+        for member in msg.members {
+            MEMBERS.save(deps.storage, &member.addr, &member.weight)?;
+        }
+        HOOKS.save(deps.storage, &vec![])?;
+        ADMIN.save(deps.storage, &msg.admin)?;
+        Ok(Response::new())
+    }
+
+    pub fn execute(deps: DepsMut, _env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+        match msg {
+            // like cw4-group, only the admin may manage the hooks
+            ExecuteMsg::AddHook { .. } | ExecuteMsg::RemoveHook { .. }
+                if info.sender != ADMIN.load(deps.storage)? =>
+            {
+                Err(StdError::generic_err("Unauthorized"))
+            },
+            ExecuteMsg::AddHook { addr } => {
+                HOOKS.update(deps.storage, |mut hooks| -> StdResult<_> {
+                    hooks.push(addr);
+                    Ok(hooks)
+                })?;
+                Ok(Response::new())
+            },
+            ExecuteMsg::RemoveHook { addr } => {
+                HOOKS.update(deps.storage, |mut hooks| -> StdResult<_> {
+                    hooks.retain(|hook| hook != &addr);
+                    Ok(hooks)
+                })?;
+                Ok(Response::new())
+            },
+            ExecuteMsg::UpdateMembers { add, remove } => {
+                let mut diffs = vec![];
+                for member in add {
+                    let old = MEMBERS.may_load(deps.storage, &member.addr)?;
+                    MEMBERS.save(deps.storage, &member.addr, &member.weight)?;
+                    diffs.push(MemberDiff { key: member.addr, old, new: Some(member.weight) });
+                }
+                for addr in remove {
+                    let old = MEMBERS.may_load(deps.storage, &addr)?;
+                    MEMBERS.remove(deps.storage, &addr);
+                    diffs.push(MemberDiff { key: addr, old, new: None });
+                }
+                let hook = to_json_binary(&AccountExecuteMsg::<Empty>::MemberChangedHook(
+                    MemberChangedHookMsg { diffs },
+                ))?;
+                let msgs = HOOKS.load(deps.storage)?.into_iter().map(|contract_addr| WasmMsg::Execute {
+                    contract_addr,
+                    msg: hook.clone(),
+                    funds: vec![],
+                });
+                Ok(Response::new().add_messages(msgs))
+            },
+        }
+    }
+
+    pub fn query(deps: Deps, _env: Env, msg: Cw4QueryMsg) -> StdResult<Binary> {
+        match msg {
+            Cw4QueryMsg::ListMembers { start_after, limit } => {
+                let members = MEMBERS
+                    .range(deps.storage, None, None, Order::Ascending)
+                    .map(|item| item.map(|(addr, weight)| Member { addr, weight }))
+                    .filter(|item| match (item, &start_after) {
+                        (Ok(member), Some(start)) => &member.addr > start,
+                        _ => true,
+                    })
+                    .take(limit.unwrap_or(10) as usize)
+                    .collect::<StdResult<Vec<_>>>()?;
+                to_json_binary(&MemberListResponse { members })
+            },
+            Cw4QueryMsg::Member { addr, .. } => {
+                to_json_binary(&MemberResponse { weight: MEMBERS.may_load(deps.storage, &addr)? })
+            },
+            Cw4QueryMsg::TotalWeight {} => {
+                let weight = MEMBERS
+                    .range(deps.storage, None, None, Order::Ascending)
+                    .map(|item| item.map(|(_, weight)| weight))
+                    .sum::<StdResult<u64>>()?;
+                to_json_binary(&TotalWeightResponse { weight })
+            },
+        }
+    }
+}
+
+#[test]
+fn exec_quorum_threshold_ignores_abstentions() {
+    let mut app = mock_app();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![
+                    Signer { addr: Addr::unchecked("owner").to_string(), weight: 1 },
+                    Signer { addr: ALICE.to_string(), weight: 1 },
+                    Signer { addr: BOB.to_string(), weight: 1 },
+                    Signer { addr: CARL.to_string(), weight: 1 },
+                ],
+                threshold: Threshold::ThresholdQuorum {
+                    threshold: Decimal::percent(60),
+                    quorum: Decimal::percent(50),
+                },
+                whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
+            },
+            &[],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::Propose {
+        title: "Open".to_string(),
+        description: String::new(),
+        msgs: vec![],
+        latest: None,
+    };
+    let _ = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &msg, &[])
+        .unwrap();
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(BOB),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::Vote { proposal_id: 1, vote: Vote::Abstain },
+            &[],
+        ).unwrap();
+
+    let resp: ProposalResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::Proposal { proposal_id: 1 })
+        .unwrap();
+    assert_eq!(resp.status, Status::Open);
+
+    // 60% of the three signers that did not abstain is two approvals
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(CARL),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::Vote { proposal_id: 1, vote: Vote::Yes },
+            &[],
+        ).unwrap();
+
+    let resp: ProposalResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::Proposal { proposal_id: 1 })
+        .unwrap();
+    assert_eq!(resp.status, Status::Passed);
+
+    // once expired, the threshold is measured against the weight that voted yes or no
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::Propose {
+        title: "Expiring".to_string(),
+        description: String::new(),
+        msgs: vec![],
+        latest: Some(Expiration::AtHeight(app.block_info().height + 5)),
+    };
+    let _ = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &msg, &[])
+        .unwrap();
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(BOB),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::Vote { proposal_id: 2, vote: Vote::Abstain },
+            &[],
+        ).unwrap();
+    app.update_block(|block| block.height += 5);
+
+    let resp: ProposalResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::Proposal { proposal_id: 2 })
+        .unwrap();
+    assert_eq!(resp.status, Status::Passed);
+
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::Close { proposal_id: 2 },
+            &[],
+        ).unwrap();
+
+    let resp: TxExecutionsResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TxExecutions {})
+        .unwrap();
+    assert_eq!(resp.tx_executions[1].status, Some(TxStatus::Passed));
+}
+
+#[test]
+fn exec_group_backed_signers_follow_the_group() {
+    let mut app = App::default();
+
+    let group_code = ContractWrapper::new(mock_group::execute, mock_group::instantiate, mock_group::query);
+    let group_code_id = app.store_code(Box::new(group_code));
+    let group = app
+        .instantiate_contract(
+            group_code_id,
+            Addr::unchecked("owner"),
+            &mock_group::InstantiateMsg {
+                // the account instantiated next administers the group
+                admin: "contract1".to_string(),
+                members: vec![
+                    Member { addr: ALICE.to_string(), weight: 1 },
+                    Member { addr: BOB.to_string(), weight: 1 },
+                ],
+            },
+            &[],
+            "Group",
+            None,
+        )
+        .unwrap();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![],
+                group: Some(group.to_string()),
+                threshold: Threshold::AbsoluteCount { weight: 2 },
+                whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+            },
+            &[],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let resp: SignerListResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::Signerlist {})
+        .unwrap();
+    assert_eq!(resp.signers, vec![
+        Signer { addr: ALICE.to_string(), weight: 1 },
+        Signer { addr: BOB.to_string(), weight: 1 },
+    ]);
+    // single members and the total weight are read from the group without listing it
+    let resp: VoterResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::Voter { address: BOB.to_string() })
+        .unwrap();
+    assert_eq!(resp.weight, Some(1));
+    let resp: ThresholdResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::Threshold {})
+        .unwrap();
+    assert_eq!(resp, ThresholdResponse::AbsoluteCount { weight: 2, total_weight: 2 });
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::AddSigners {
+        signers: vec![Signer { addr: CARL.to_string(), weight: 1 }],
+    };
+    let err = app
+        .execute_contract(Addr::unchecked("owner"), addr.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::SignersManagedByGroup {}, err.downcast().unwrap());
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction { msgs: vec![], expires: None };
+    let _ = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &msg, &[])
+        .unwrap();
+
+    // only the group may report membership changes
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::MemberChangedHook(MemberChangedHookMsg { diffs: vec![] });
+    let err = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized { sender: Addr::unchecked(ALICE) },
+        err.downcast().unwrap(),
+    );
+
+    // alice leaves the group and her approval no longer counts
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            group.clone(),
+            &mock_group::ExecuteMsg::UpdateMembers {
+                add: vec![Member { addr: CARL.to_string(), weight: 1 }],
+                remove: vec![ALICE.to_string()],
+            },
+            &[],
+        )
+        .unwrap();
+
+    let resp: TxExecutionsResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TxExecutions {})
+        .unwrap();
+    assert_eq!(resp.tx_executions[0].status, Some(TxStatus::Pending));
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(ALICE),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::SignTransaction { tx_id: 1 },
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized { sender: Addr::unchecked(ALICE) },
+        err.downcast().unwrap(),
+    );
+    for signer in [BOB, CARL] {
+        let _ = app
+            .execute_contract(
+                Addr::unchecked(signer),
+                addr.clone(),
+                &ExecuteMsg::<Empty>::SignTransaction { tx_id: 1 },
+                &[],
+            ).unwrap();
+    }
+    let resp: TxExecutionsResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TxExecutions {})
+        .unwrap();
+    assert_eq!(resp.tx_executions[0].status, Some(TxStatus::Passed));
+}
+
+#[test]
+fn exec_group_changes_are_rechecked_at_execution() {
+    let mut app = App::default();
+
+    let group_code = ContractWrapper::new(mock_group::execute, mock_group::instantiate, mock_group::query);
+    let group_code_id = app.store_code(Box::new(group_code));
+    let group = app
+        .instantiate_contract(
+            group_code_id,
+            Addr::unchecked("owner"),
+            &mock_group::InstantiateMsg {
+                // the account instantiated next administers the group
+                admin: "contract1".to_string(),
+                members: vec![
+                    Member { addr: ALICE.to_string(), weight: 1 },
+                    Member { addr: BOB.to_string(), weight: 1 },
+                ],
+            },
+            &[],
+            "Group",
+            None,
+        )
+        .unwrap();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let instantiate_msg = |group: &Addr| InstantiateMsg {
+        admin: Some(Addr::unchecked("owner").to_string()),
+        signers: vec![],
+        group: Some(group.to_string()),
+        threshold: Threshold::AbsoluteCount { weight: 2 },
+        whitelist_enabled: false,
+        default_expiry: None,
+        open_execution: false,
+    };
+    let addr = app
+        .instantiate_contract(code_id, Addr::unchecked("owner"), &instantiate_msg(&group), &[], "Contract", None)
+        .unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction { msgs: vec![], expires: None };
+    let _ = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &msg, &[])
+        .unwrap();
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(BOB),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::SignTransaction { tx_id: 1 },
+            &[],
+        ).unwrap();
+
+    // bob is replaced while the group no longer reports changes to the account
+    let _ = app
+        .execute_contract(
+            addr.clone(),
+            group.clone(),
+            &mock_group::ExecuteMsg::RemoveHook { addr: addr.to_string() },
+            &[],
+        )
+        .unwrap();
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            group.clone(),
+            &mock_group::ExecuteMsg::UpdateMembers {
+                add: vec![Member { addr: CARL.to_string(), weight: 1 }],
+                remove: vec![BOB.to_string()],
+            },
+            &[],
+        )
+        .unwrap();
+
+    // the stale approval of bob no longer counts, so the transaction goes back to voting
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteApproved { tx_id: 1 };
+    let _ = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &msg, &[])
+        .unwrap();
+    let resp: TxExecutionsResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TxExecutions {})
+        .unwrap();
+    assert_eq!(resp.tx_executions[0].status, Some(TxStatus::Pending));
+    let err = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidStatus { tx_id: 1 }, err.downcast().unwrap());
+
+    // an account that may not add hooks to the group can't be instantiated
+    let other_group = app
+        .instantiate_contract(
+            group_code_id,
+            Addr::unchecked("owner"),
+            &mock_group::InstantiateMsg {
+                admin: "owner".to_string(),
+                members: vec![
+                    Member { addr: ALICE.to_string(), weight: 1 },
+                    Member { addr: BOB.to_string(), weight: 1 },
+                ],
+            },
+            &[],
+            "Group",
+            None,
+        )
+        .unwrap();
+    let err = app
+        .instantiate_contract(code_id, Addr::unchecked("owner"), &instantiate_msg(&other_group), &[], "Contract", None)
+        .unwrap_err();
+    assert!(matches!(err.downcast().unwrap(), ContractError::GroupHookFailed { .. }));
+}
+
+#[test]
+fn exec_allowance_dispatches_small_sends() {
+    let mut app = mock_app();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![
+                    Signer { addr: ALICE.to_string(), weight: 1 },
+                    Signer { addr: BOB.to_string(), weight: 1 },
+                ],
+                threshold: Threshold::AbsoluteCount { weight: 2 },
+                whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
+            },
+            &[coin(1000, DENOM)],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::SetAllowance {
+        signer: ALICE.to_string(),
+        limits: vec![coin(50, DENOM)],
+        period: Duration::Height(100),
+    };
+    let err = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::MissingPermission {
+            sender: Addr::unchecked(ALICE),
+            permission: Permission::ManagePolicy,
+        },
+        err.downcast().unwrap(),
+    );
+    let _ = app
+        .execute_contract(Addr::unchecked("owner"), addr.clone(), &msg, &[])
+        .unwrap();
+
+    let send = |amount: u128| -> ExecuteMsg<Empty> {
+        ExecuteMsg::ExecuteTransaction {
+            msgs: vec![BankMsg::Send {
+                to_address: CARL.to_string(),
+                amount: vec![coin(amount, DENOM)],
+            }.into()],
+            expires: None,
+        }
+    };
+
+    // within the allowance the send goes out at once
+    let _ = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &send(30), &[])
+        .unwrap();
+    assert_eq!(app.wrap().query_balance(CARL, DENOM).unwrap(), coin(30, DENOM));
+
+    let resp: AllowanceResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::Allowance { signer: ALICE.to_string() })
+        .unwrap();
+    assert_eq!(resp.allowance.unwrap().spent, vec![coin(30, DENOM)]);
+    assert_eq!(resp.remaining, vec![coin(20, DENOM)]);
+
+    // above the remaining allowance the send needs the threshold
+    let _ = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &send(30), &[])
+        .unwrap();
+    assert_eq!(app.wrap().query_balance(CARL, DENOM).unwrap(), coin(30, DENOM));
+
+    // bob has no allowance
+    let _ = app
+        .execute_contract(Addr::unchecked(BOB), addr.clone(), &send(10), &[])
+        .unwrap();
+    let resp: TxExecutionsResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TxExecutions {})
+        .unwrap();
+    let statuses: Vec<Option<TxStatus>> = resp.tx_executions.into_iter().map(|tx| tx.status).collect();
+    assert_eq!(statuses, vec![Some(TxStatus::Done), Some(TxStatus::Pending), Some(TxStatus::Pending)]);
+
+    // the allowance is restored in the next period
+    app.update_block(|block| block.height += 100);
+    let _ = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &send(50), &[])
+        .unwrap();
+    assert_eq!(app.wrap().query_balance(CARL, DENOM).unwrap(), coin(80, DENOM));
+
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::RemoveAllowance { signer: ALICE.to_string() },
+            &[],
+        )
+        .unwrap();
+    let resp: AllowanceResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::Allowance { signer: ALICE.to_string() })
+        .unwrap();
+    assert_eq!(resp, AllowanceResponse { allowance: None, remaining: vec![] });
+}
+
+#[test]
+fn exec_failed_sends_are_refunded() {
+    let mut app = mock_app();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![
+                    Signer { addr: ALICE.to_string(), weight: 1 },
+                    Signer { addr: BOB.to_string(), weight: 1 },
+                ],
+                threshold: Threshold::AbsoluteCount { weight: 2 },
+                whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
+            },
+            &[coin(100, DENOM)],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::SetAllowance {
+        signer: ALICE.to_string(),
+        limits: vec![coin(500, DENOM)],
+        period: Duration::Height(100),
+    };
+    let _ = app
+        .execute_contract(Addr::unchecked("owner"), addr.clone(), &msg, &[])
+        .unwrap();
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::SetTransferWindow {
+        limits: vec![coin(1000, DENOM)],
+        period: Duration::Height(100),
+    };
+    let _ = app
+        .execute_contract(Addr::unchecked("owner"), addr.clone(), &msg, &[])
+        .unwrap();
+
+    let send = |amount: u128| -> ExecuteMsg<Empty> {
+        ExecuteMsg::ExecuteTransaction {
+            msgs: vec![BankMsg::Send {
+                to_address: CARL.to_string(),
+                amount: vec![coin(amount, DENOM)],
+            }.into()],
+            expires: None,
+        }
+    };
+    let assert_spent = |app: &App, spent: Vec<Coin>| {
+        let resp: AllowanceResponse = app
+            .wrap()
+            .query_wasm_smart(addr.clone(), &QueryMsg::Allowance { signer: ALICE.to_string() })
+            .unwrap();
+        assert_eq!(resp.allowance.unwrap().spent, spent);
+        let resp: TransferWindowResponse = app
+            .wrap()
+            .query_wasm_smart(addr.clone(), &QueryMsg::TransferWindow {})
+            .unwrap();
+        assert_eq!(resp.window.unwrap().spent, spent);
+    };
+
+    let _ = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &send(80), &[])
+        .unwrap();
+    assert_spent(&app, vec![coin(80, DENOM)]);
+
+    // a send from the allowance that overdraws the account gives its amount back
+    let _ = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &send(300), &[])
+        .unwrap();
+    assert_spent(&app, vec![coin(80, DENOM)]);
+
+    // an approved send that fails only gives back the transfer window
+    let _ = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &send(450), &[])
+        .unwrap();
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(BOB),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::SignTransaction { tx_id: 3 },
+            &[],
+        ).unwrap();
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(BOB),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::ExecuteApproved { tx_id: 3 },
+            &[],
+        ).unwrap();
+    assert_spent(&app, vec![coin(80, DENOM)]);
+
+    let resp: TxExecutionsResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TxExecutions {})
+        .unwrap();
+    let statuses: Vec<Option<TxStatus>> = resp.tx_executions.into_iter().map(|tx| tx.status).collect();
+    assert_eq!(statuses, vec![Some(TxStatus::Done), Some(TxStatus::Failed), Some(TxStatus::Failed)]);
+    assert_eq!(app.wrap().query_balance(CARL, DENOM).unwrap(), coin(80, DENOM));
+}
+
+#[test]
+fn exec_transfer_window_caps_cumulative_outflow() {
+    let mut app = mock_app();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![Signer { addr: ALICE.to_string(), weight: 1 }],
+                threshold: Threshold::AbsoluteCount { weight: 1 },
+                whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
+            },
+            &[coin(5000, DENOM)],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::SetTransferWindow {
+        limits: vec![coin(1100, DENOM)],
+        period: Duration::Height(100),
+    };
+    let _ = app
+        .execute_contract(Addr::unchecked("owner"), addr.clone(), &msg, &[])
+        .unwrap();
+
+    let send: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction {
+        msgs: vec![BankMsg::Send {
+            to_address: CARL.to_string(),
+            amount: vec![coin(1000, DENOM)],
+        }.into()],
+        expires: None,
+    };
+    for _ in 0..2 {
+        let _ = app
+            .execute_contract(Addr::unchecked(ALICE), addr.clone(), &send, &[])
+            .unwrap();
+    }
+
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(ALICE),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::ExecuteApproved { tx_id: 1 },
+            &[],
+        ).unwrap();
+    let resp: TransferWindowResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TransferWindow {})
+        .unwrap();
+    assert_eq!(resp.window.unwrap().spent, vec![coin(1000, DENOM)]);
+    assert_eq!(resp.remaining, vec![coin(100, DENOM)]);
+
+    // updating the limits does not forget what was sent in the current period
+    let resets_at = Expiration::AtHeight(app.block_info().height + 100);
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::SetTransferWindow {
+        limits: vec![coin(1500, DENOM)],
+        period: Duration::Height(100),
+    };
+    let _ = app
+        .execute_contract(Addr::unchecked("owner"), addr.clone(), &msg, &[])
+        .unwrap();
+    let resp: TransferWindowResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TransferWindow {})
+        .unwrap();
+    let window = resp.window.unwrap();
+    assert_eq!(window.spent, vec![coin(1000, DENOM)]);
+    assert_eq!(window.resets_at, resets_at);
+    assert_eq!(resp.remaining, vec![coin(500, DENOM)]);
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::SetTransferWindow {
+        limits: vec![coin(1100, DENOM)],
+        period: Duration::Height(100),
+    };
+    let _ = app
+        .execute_contract(Addr::unchecked("owner"), addr.clone(), &msg, &[])
+        .unwrap();
+
+    // the second transfer stays approved until the window resets
+    let err = app
+        .execute_contract(
+            Addr::unchecked(ALICE),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::ExecuteApproved { tx_id: 2 },
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::WindowLimitExceeded { amount: coin(2000, DENOM) },
+        err.downcast().unwrap(),
+    );
+    assert_eq!(app.wrap().query_balance(CARL, DENOM).unwrap(), coin(1000, DENOM));
+
+    app.update_block(|block| block.height += 100);
+    let resp: TransferWindowResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::TransferWindow {})
+        .unwrap();
+    assert_eq!(resp.remaining, vec![coin(1100, DENOM)]);
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(ALICE),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::ExecuteApproved { tx_id: 2 },
+            &[],
+        ).unwrap();
+    assert_eq!(app.wrap().query_balance(CARL, DENOM).unwrap(), coin(2000, DENOM));
+}
+
+#[test]
+fn exec_transfer_limits_apply_to_total_outflow() {
+    let mut app = mock_app();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![Signer { addr: ALICE.to_string(), weight: 1 }],
+                threshold: Threshold::AbsoluteCount { weight: 1 },
+                whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
+            },
+            &[coin(5000, DENOM)],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::SetTransferLimits {
+        coins: vec![coin(1100, DENOM)],
+    };
+    let _ = app
+        .execute_contract(Addr::unchecked("owner"), addr.clone(), &msg, &[])
+        .unwrap();
+
+    // every send is below the limit on its own, but not together
+    let send: CosmosMsg = BankMsg::Send {
+        to_address: CARL.to_string(),
+        amount: vec![coin(600, DENOM)],
+    }.into();
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction {
+        msgs: vec![send.clone(), send.clone()],
+        expires: None,
+    };
+    let err = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NotAllowedAmount { amount: coin(1200, DENOM) },
+        err.downcast().unwrap(),
+    );
+
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction {
+        msgs: vec![send],
+        expires: None,
+    };
+    let _ = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &msg, &[])
+        .unwrap();
+}
+
+#[test]
+fn exec_cw20_transfers_follow_policy() {
+    let mut app = mock_app();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![
+                    Signer { addr: ALICE.to_string(), weight: 1 },
+                    Signer { addr: BOB.to_string(), weight: 1 },
+                ],
+                threshold: Threshold::AbsoluteCount { weight: 2 },
+                whitelist_enabled: true,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
+            },
+            &[],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::SetWhitelistAddresses { addresses: vec![CARL.to_string()] },
+            &[],
+        )
+        .unwrap();
+    // limits of cw20 tokens are keyed by the token contract address
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::SetTransferLimits { coins: vec![coin(100, "token")] },
+            &[],
+        )
+        .unwrap();
+
+    let cw20_msg = |msg: Cw20ExecuteMsg| -> CosmosMsg {
+        WasmMsg::Execute {
+            contract_addr: "token".to_string(),
+            msg: to_json_binary(&msg).unwrap(),
+            funds: vec![],
+        }.into()
+    };
+    let propose = |msgs: Vec<CosmosMsg>| -> ExecuteMsg<Empty> {
+        ExecuteMsg::ExecuteTransaction { msgs, expires: None }
+    };
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(ALICE),
+            addr.clone(),
+            &propose(vec![cw20_msg(Cw20ExecuteMsg::IncreaseAllowance {
+                spender: BOB.to_string(),
+                amount: Uint128::new(10),
+                expires: None,
+            })]),
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::NotAllowedRecipient { recipient: BOB.to_string() },
+        err.downcast().unwrap(),
+    );
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(ALICE),
+            addr.clone(),
+            &propose(vec![
+                cw20_msg(Cw20ExecuteMsg::Transfer { recipient: CARL.to_string(), amount: Uint128::new(60) }),
+                cw20_msg(Cw20ExecuteMsg::Send {
+                    contract: CARL.to_string(),
+                    amount: Uint128::new(60),
+                    msg: Binary::default(),
+                }),
+            ]),
+            &[],
+        ).unwrap_err();
+    assert_eq!(
+        ContractError::NotAllowedAmount { amount: coin(120, "token") },
+        err.downcast().unwrap(),
+    );
+
+    let _ = app
+        .execute_contract(
+            Addr::unchecked(ALICE),
+            addr.clone(),
+            &propose(vec![cw20_msg(Cw20ExecuteMsg::TransferFrom {
+                owner: BOB.to_string(),
+                recipient: CARL.to_string(),
+                amount: Uint128::new(100),
+            })]),
+            &[],
+        ).unwrap();
+}
+
+#[test]
+fn exec_wasm_funds_follow_policy() {
+    let mut app = mock_app();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![Signer { addr: ALICE.to_string(), weight: 1 }],
+                threshold: Threshold::AbsoluteCount { weight: 1 },
+                whitelist_enabled: true,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
+            },
+            &[],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::SetWhitelistAddresses { addresses: vec!["dex".to_string()] },
+            &[],
+        )
+        .unwrap();
+    let _ = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &ExecuteMsg::<Empty>::SetTransferLimits { coins: vec![coin(100, DENOM)] },
+            &[],
+        )
+        .unwrap();
+
+    let call = |contract_addr: &str, amount: u128| -> CosmosMsg {
+        WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: to_json_binary(&Empty {}).unwrap(),
+            funds: vec![coin(amount, DENOM)],
+        }.into()
+    };
+    let propose = |msgs: Vec<CosmosMsg>| -> ExecuteMsg<Empty> {
+        ExecuteMsg::ExecuteTransaction { msgs, expires: None }
+    };
+
+    let err = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &propose(vec![call("drain", 10)]), &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NotAllowedRecipient { recipient: "drain".to_string() },
+        err.downcast().unwrap(),
+    );
+
+    // attached funds add up with bank sends against the transfer limits
+    let send: CosmosMsg = BankMsg::Send {
+        to_address: "dex".to_string(),
+        amount: vec![coin(50, DENOM)],
+    }.into();
+    let err = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &propose(vec![call("dex", 60), send]), &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NotAllowedAmount { amount: coin(110, DENOM) },
+        err.downcast().unwrap(),
+    );
+
+    let instantiate: CosmosMsg = WasmMsg::Instantiate {
+        admin: None,
+        code_id: 7,
+        msg: to_json_binary(&Empty {}).unwrap(),
+        funds: vec![coin(10, DENOM)],
+        label: "new".to_string(),
+    }.into();
+    let err = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &propose(vec![instantiate]), &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NotAllowedInstantiateFunds { code_id: 7 },
+        err.downcast().unwrap(),
+    );
+
+    let _ = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &propose(vec![call("dex", 100)]), &[])
+        .unwrap();
+}
+
+#[test]
+fn exec_allowed_msg_types() {
+    let mut app = mock_app();
+
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+    let code_id = app.store_code(Box::new(code));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                admin: Some(Addr::unchecked("owner").to_string()),
+                signers: vec![Signer { addr: ALICE.to_string(), weight: 1 }],
+                threshold: Threshold::AbsoluteCount { weight: 1 },
+                whitelist_enabled: false,
+                default_expiry: None,
+                open_execution: false,
+                group: None,
+            },
+            &[],
+            "Contract",
+            None,
+        )
+        .unwrap();
+
+    let resp: AllowedMsgTypesResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::AllowedMsgTypes {})
+        .unwrap();
+    assert_eq!(resp.msg_types, MsgType::defaults());
+
+    // contract admin messages are not allowed by default
+    let update_admin: CosmosMsg = WasmMsg::UpdateAdmin {
+        contract_addr: "dex".to_string(),
+        admin: ALICE.to_string(),
+    }.into();
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction {
+        msgs: vec![update_admin],
+        expires: None,
+    };
+    let err = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NotAllowedMsgType { msg_type: MsgType::WasmUpdateAdmin },
+        err.downcast().unwrap(),
+    );
+
+    let set_types: ExecuteMsg<Empty> = ExecuteMsg::SetAllowedMsgTypes {
+        msg_types: vec![MsgType::BankSend, MsgType::WasmUpdateAdmin, MsgType::BankSend],
+    };
+    let err = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &set_types, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::MissingPermission {
+            sender: Addr::unchecked(ALICE),
+            permission: Permission::ManagePolicy,
+        },
+        err.downcast().unwrap(),
+    );
+    let _ = app
+        .execute_contract(Addr::unchecked("owner"), addr.clone(), &set_types, &[])
+        .unwrap();
+
+    let resp: AllowedMsgTypesResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::AllowedMsgTypes {})
+        .unwrap();
+    assert_eq!(resp.msg_types, vec![MsgType::BankSend, MsgType::WasmUpdateAdmin]);
+
+    let _ = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &msg, &[])
+        .unwrap();
+
+    let call: CosmosMsg = WasmMsg::Execute {
+        contract_addr: "dex".to_string(),
+        msg: to_json_binary(&Empty {}).unwrap(),
+        funds: vec![],
+    }.into();
+    let msg: ExecuteMsg<Empty> = ExecuteMsg::ExecuteTransaction {
+        msgs: vec![call],
+        expires: None,
+    };
+    let err = app
+        .execute_contract(Addr::unchecked(ALICE), addr.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NotAllowedMsgType { msg_type: MsgType::WasmExecute },
+        err.downcast().unwrap(),
+    );
+}
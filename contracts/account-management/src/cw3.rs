@@ -0,0 +1,96 @@
+// message and response types of the cw3 multisig specification, mirrored from cw3 0.13
+// so standard cw3 tooling can drive the account
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{CosmosMsg, Empty};
+use cw_utils::{Expiration, ThresholdResponse};
+
+use crate::state::TxStatus;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Vote {
+    // marks support for the proposal
+    Yes,
+    // marks opposition to the proposal
+    No,
+    // counts towards the quorum without taking a side
+    Abstain,
+    // treated as a no vote, the account has no separate veto
+    Veto,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Pending,
+    Open,
+    Rejected,
+    Passed,
+    Executed,
+}
+
+impl From<&TxStatus> for Status {
+    fn from(status: &TxStatus) -> Self {
+        match status {
+            TxStatus::Pending => Status::Open,
+            TxStatus::Passed => Status::Passed,
+            TxStatus::Done => Status::Executed,
+            TxStatus::Failed
+            | TxStatus::Expired
+            | TxStatus::Rejected
+            | TxStatus::Cancelled => Status::Rejected,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalResponse<T = Empty> {
+    pub id: u64,
+    pub title: String,
+    pub description: String,
+    pub msgs: Vec<CosmosMsg<T>>,
+    pub status: Status,
+    pub expires: Expiration,
+    pub threshold: ThresholdResponse,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalListResponse {
+    pub proposals: Vec<ProposalResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VoteInfo {
+    pub proposal_id: u64,
+    pub voter: String,
+    pub vote: Vote,
+    pub weight: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VoteResponse {
+    pub vote: Option<VoteInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VoteListResponse {
+    pub votes: Vec<VoteInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VoterResponse {
+    pub weight: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VoterDetail {
+    pub addr: String,
+    pub weight: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VoterListResponse {
+    pub voters: Vec<VoterDetail>,
+}
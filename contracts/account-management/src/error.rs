@@ -1,4 +1,7 @@
 use cosmwasm_std::{Addr, Coin, StdError};
+use cw_utils::ThresholdError;
+
+use crate::state::{MsgType, Permission, Role};
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -6,18 +9,123 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("Cannot migrate from different contract type: {previous_contract}")]
+    CannotMigrate { previous_contract: String },
+
+    #[error("Cannot migrate from newer version: {previous_version}")]
+    CannotMigrateVersion { previous_version: String },
+
+    #[error("Cannot parse contract version: {version}")]
+    InvalidVersion { version: String },
+
     #[error("{sender} is not contract admin")]
     Unauthorized { sender: Addr },
 
-    #[error("Threshold {threshold} is out of range")]
-    InvalidThreshold { threshold: u8 },
+    #[error("{sender} does not have {permission:?} permission")]
+    MissingPermission { sender: Addr, permission: Permission },
+
+    #[error("{address} does not have {role:?} role")]
+    RoleNotGranted { address: Addr, role: Role },
+
+    #[error("{0}")]
+    Threshold(#[from] ThresholdError),
+
+    #[error("At least one signer is required")]
+    NoSigners {},
+
+    #[error("The summed weight of the signers does not fit in a u64")]
+    WeightOverflow {},
+
+    #[error("{signer} must have a weight greater than zero")]
+    ZeroWeight { signer: Addr },
+
+    #[error("{sender} is not a guardian")]
+    NotGuardian { sender: Addr },
+
+    #[error("Signers are managed by the cw4 group")]
+    SignersManagedByGroup {},
+
+    #[error("Cannot register the member changed hook with the group: {error}")]
+    GroupHookFailed { error: String },
+
+    #[error("Account is paused")]
+    Paused {},
+
+    #[error("Account is not paused")]
+    NotPaused {},
+
+    #[error("{voter} already voted to unpause")]
+    AlreadyVotedUnpause { voter: Addr },
+
+    #[error("Recovery is not configured")]
+    RecoveryDisabled {},
+
+    #[error("Recovery threshold must be at least one guardian")]
+    InvalidRecoveryThreshold {},
+
+    #[error("A recovery is already in progress")]
+    RecoveryInProgress {},
+
+    #[error("There is no recovery in progress")]
+    NoRecovery {},
+
+    #[error("{guardian} already approved the recovery")]
+    RecoveryAlreadyApproved { guardian: Addr },
+
+    #[error("Recovery is not approved or its delay has not elapsed")]
+    RecoveryNotReady {},
 
     #[error("Status {tx_id} is not allowed")]
     InvalidStatus { tx_id: u16 },
 
+    #[error("Transaction {tx_id} is expired")]
+    Expired { tx_id: u16 },
+
+    #[error("{signer} already signed transaction {tx_id}")]
+    AlreadySigned { tx_id: u16, signer: Addr },
+
+    #[error("{signer} has not signed transaction {tx_id}")]
+    NotSigned { tx_id: u16, signer: Addr },
+
+    #[error("{voter} already voted on transaction {tx_id}")]
+    AlreadyVoted { tx_id: u16, voter: Addr },
+
+    #[error("Transaction {tx_id} has not expired yet")]
+    NotExpired { tx_id: u16 },
+
+    #[error("Transaction {tx_id} is still timelocked")]
+    Timelocked { tx_id: u16 },
+
+    #[error("Sending {amount} in the current window exceeds the transfer window limit")]
+    WindowLimitExceeded { amount: Coin },
+
+    #[error("{signer} has no allowance")]
+    NoAllowance { signer: Addr },
+
+    #[error("There is no pending admin")]
+    NoPendingAdmin {},
+
+    #[error("Pending admin nomination is expired")]
+    PendingAdminExpired {},
+
+    #[error("Expiration is already passed")]
+    InvalidExpiration {},
+
+    #[error("{msg_type:?} messages are not allowed")]
+    NotAllowedMsgType { msg_type: MsgType },
+
+    #[error("Message type is not supported")]
+    UnsupportedMsg {},
+
+    #[error("A self governed account must allow WasmExecute messages to change its configuration")]
+    SelfGovernanceLocked {},
+
     #[error("{recipient} is not whitelisted")]
     NotAllowedRecipient { recipient: String },
 
-    #[error("{amount} is not allowed")]
+    #[error("A new instance of code {code_id} can't be funded while the whitelist is enabled")]
+    NotAllowedInstantiateFunds { code_id: u64 },
+
+    #[error("Total transfer of {amount} is not allowed")]
     NotAllowedAmount { amount: Coin },
 }
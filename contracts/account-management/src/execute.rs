@@ -1,64 +1,209 @@
 use std::collections::{HashMap, HashSet};
 
 use cosmwasm_std::{
-    Addr, BankMsg, Coin, CosmosMsg, DepsMut, MessageInfo, Response, Uint128
+    to_json_binary, to_json_string, Addr, BlockInfo, Coin, CosmosMsg, DepsMut, Empty, Env, MessageInfo, Reply,
+    Response, StdResult, Storage, SubMsg, SubMsgResult, Uint128, WasmMsg,
 };
+use cw_utils::{Duration, Expiration, Threshold};
+use crate::cw3::Vote;
+use crate::cw4::MemberDiff;
 use crate::error::ContractError;
 use crate::state::{
-    TxData, TxStatus, POLICY, STATE, TX_EXECUTION, TX_NEXT_ID
+    MsgResult, MsgType, Pause, PendingAdmin, Permission, Policy, Recovery, RecoveryConfig, Role, SpendWindow, State, TxData, TxStatus,
+    ALLOWANCES, PAUSE, PENDING_ADMIN, POLICY, RECOVERY, ROLES, STATE, TRANSFER_WINDOW,
+    TX_EXECUTION, TX_NEXT_ID,
 };
 use crate::helpers::{
-    is_sufficient_signers, is_valid_threshold,
-    map_validate, validate_addr,
+    check_policy, from_reply_id, guardian_approvals, has_permission, is_guardian, load_members, load_state,
+    map_validate, map_validate_signers, sum_bank_sends, to_reply_id, total_outflows, validate_addr,
 };
+use crate::msg::{ExecuteMsg, Signer};
 
 pub fn change_admin(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     new_admin: String,
+    expires: Option<Expiration>,
 ) -> Result<Response, ContractError> {
-    let mut curr_state = STATE.load(deps.storage)?;
+    let curr_state = load_state(deps.as_ref())?;
     if !curr_state.can_modify(info.sender.as_ref()) {
         return Err(ContractError::Unauthorized {
             sender: info.sender,
         });
     }
-    
-    curr_state.admin = validate_addr(deps.api, &new_admin)?;
-    STATE.save(deps.storage, &curr_state)?;
+
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::InvalidExpiration {});
+    }
+
+    // the new admin only takes over after accepting the nomination
+    let pending_admin = PendingAdmin {
+        address: validate_addr(deps.api, &new_admin)?,
+        expires,
+    };
+    PENDING_ADMIN.save(deps.storage, &pending_admin)?;
 
     Ok(
         Response::new()
             .add_attribute("action", "change_admin")
-            .add_attribute("new_admin", new_admin)
+            .add_attribute("pending_admin", new_admin)
     )
 }
 
-pub fn change_threshold(
+pub fn accept_admin(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let pending_admin = PENDING_ADMIN
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoPendingAdmin {})?;
+    if pending_admin.address != info.sender {
+        return Err(ContractError::Unauthorized {
+            sender: info.sender,
+        });
+    }
+    if pending_admin.expires.is_expired(&env.block) {
+        return Err(ContractError::PendingAdminExpired {});
+    }
+
+    let mut curr_state = load_state(deps.as_ref())?;
+    curr_state.self_governed = pending_admin.address == env.contract.address;
+    if curr_state.self_governed
+        && !POLICY.load(deps.storage)?.allowed_msg_types.contains(&MsgType::WasmExecute)
+    {
+        return Err(ContractError::SelfGovernanceLocked {});
+    }
+    curr_state.admin = pending_admin.address;
+    STATE.save(deps.storage, &curr_state)?;
+    PENDING_ADMIN.remove(deps.storage);
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "accept_admin")
+            .add_attribute("new_admin", info.sender)
+    )
+}
+
+pub fn cancel_admin_transfer(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let curr_state = load_state(deps.as_ref())?;
+    if !curr_state.can_modify(info.sender.as_ref()) {
+        return Err(ContractError::Unauthorized {
+            sender: info.sender,
+        });
+    }
+    if PENDING_ADMIN.may_load(deps.storage)?.is_none() {
+        return Err(ContractError::NoPendingAdmin {});
+    }
+    PENDING_ADMIN.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "cancel_admin_transfer"))
+}
+
+pub fn grant_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    role: Role,
+) -> Result<Response, ContractError> {
+    let curr_state = load_state(deps.as_ref())?;
+    if !curr_state.can_modify(info.sender.as_ref()) {
+        return Err(ContractError::Unauthorized {
+            sender: info.sender,
+        });
+    }
+
+    let address = validate_addr(deps.api, &address)?;
+    let mut roles = ROLES.may_load(deps.storage, &address)?.unwrap_or_default();
+    if !roles.contains(&role) {
+        roles.push(role);
+    }
+    ROLES.save(deps.storage, &address, &roles)?;
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "grant_role")
+            .add_attribute("address", address)
+            .add_attribute("role", to_json_string(&role)?)
+    )
+}
+
+pub fn revoke_role(
     deps: DepsMut,
     info: MessageInfo,
-    new_threshold: u8,
+    address: String,
+    role: Role,
 ) -> Result<Response, ContractError> {
-    let mut curr_state = STATE.load(deps.storage)?;
+    let curr_state = load_state(deps.as_ref())?;
     if !curr_state.can_modify(info.sender.as_ref()) {
         return Err(ContractError::Unauthorized {
             sender: info.sender,
         });
     }
 
-    if !is_valid_threshold(new_threshold, curr_state.signers.len()) {
-        return Err(ContractError::InvalidThreshold {
-            threshold: new_threshold,
+    let address = validate_addr(deps.api, &address)?;
+    let mut roles = ROLES.may_load(deps.storage, &address)?.unwrap_or_default();
+    if !roles.contains(&role) {
+        return Err(ContractError::RoleNotGranted {
+            address,
+            role,
+        });
+    }
+    roles.retain(|r| *r != role);
+    if roles.is_empty() {
+        ROLES.remove(deps.storage, &address);
+    } else {
+        ROLES.save(deps.storage, &address, &roles)?;
+    }
+
+    // a revoked guardian no longer backs the recovery in progress
+    if role == Role::Guardian {
+        if let Some(mut recovery) = RECOVERY.may_load(deps.storage)? {
+            recovery.approvals.retain(|approver| *approver != address);
+            let threshold = curr_state.recovery_config.map_or(u64::MAX, |c| c.threshold);
+            if guardian_approvals(deps.storage, &recovery)? < threshold {
+                recovery.executable_at = None;
+            }
+            RECOVERY.save(deps.storage, &recovery)?;
+        }
+    }
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "revoke_role")
+            .add_attribute("address", address)
+            .add_attribute("role", to_json_string(&role)?)
+    )
+}
+
+pub fn change_threshold(
+    deps: DepsMut,
+    info: MessageInfo,
+    new_threshold: Threshold,
+) -> Result<Response, ContractError> {
+    let mut curr_state = load_state(deps.as_ref())?;
+    if !has_permission(deps.storage, &curr_state, &info.sender, Permission::ManageSigners)? {
+        return Err(ContractError::MissingPermission {
+            sender: info.sender,
+            permission: Permission::ManageSigners,
         });
     }
+
+    curr_state.validate_threshold(&new_threshold)?;
     
+    let attr_threshold = to_json_string(&new_threshold)?;
     curr_state.threshold = new_threshold;
     STATE.save(deps.storage, &curr_state)?;
 
     Ok(
         Response::new()
             .add_attribute("action", "change_threshold")
-            .add_attribute("new_threshold", new_threshold.to_string())
+            .add_attribute("new_threshold", attr_threshold)
     )
 }
 
@@ -67,10 +212,11 @@ pub fn change_whitelist_enabled(
     info: MessageInfo,
     enabled: bool,
 ) -> Result<Response, ContractError> {
-    let curr_state = STATE.load(deps.storage)?;
-    if !curr_state.can_modify(info.sender.as_ref()) {
-        return Err(ContractError::Unauthorized {
+    let curr_state = load_state(deps.as_ref())?;
+    if !has_permission(deps.storage, &curr_state, &info.sender, Permission::ManagePolicy)? {
+        return Err(ContractError::MissingPermission {
             sender: info.sender,
+            permission: Permission::ManagePolicy,
         });
     }
 
@@ -88,17 +234,23 @@ pub fn change_whitelist_enabled(
 pub fn add_signers(
     deps: DepsMut,
     info: MessageInfo,
-    signers: Vec<String>,
+    signers: Vec<Signer>,
 ) -> Result<Response, ContractError> {
-    let mut curr_state = STATE.load(deps.storage)?;
-    if !curr_state.can_modify(info.sender.as_ref()) {
-        return Err(ContractError::Unauthorized {
+    let mut curr_state = load_state(deps.as_ref())?;
+    if !has_permission(deps.storage, &curr_state, &info.sender, Permission::ManageSigners)? {
+        return Err(ContractError::MissingPermission {
             sender: info.sender,
+            permission: Permission::ManageSigners,
         });
     }
+
+    if curr_state.group.is_some() {
+        return Err(ContractError::SignersManagedByGroup {});
+    }
     
-    let mut signers = map_validate(deps.api, &signers)?;
-    curr_state.signers.append(&mut signers);
+    let signers = map_validate_signers(deps.api, &signers)?;
+    curr_state.upsert_signers(signers);
+    curr_state.validate_threshold(&curr_state.threshold)?;
     STATE.save(deps.storage, &curr_state)?;
 
     Ok(Response::new().add_attribute("action", "add_signers"))
@@ -109,232 +261,756 @@ pub fn remove_signers (
     info: MessageInfo,
     signers: Vec<String>,
 ) -> Result<Response, ContractError> {
-    let mut curr_state = STATE.load(deps.storage)?;
-    if !curr_state.can_modify(info.sender.as_ref()) {
-        return Err(ContractError::Unauthorized {
+    let mut curr_state = load_state(deps.as_ref())?;
+    if !has_permission(deps.storage, &curr_state, &info.sender, Permission::ManageSigners)? {
+        return Err(ContractError::MissingPermission {
             sender: info.sender,
+            permission: Permission::ManageSigners,
         });
     }
+
+    if curr_state.group.is_some() {
+        return Err(ContractError::SignersManagedByGroup {});
+    }
     let signers = map_validate(deps.api, &signers)?;
-    curr_state.signers.retain(|curr_member| !signers.contains(curr_member));
+    curr_state.signers.retain(|curr_member| !signers.contains(&curr_member.addr));
+    curr_state.validate_threshold(&curr_state.threshold)?;
     STATE.save(deps.storage, &curr_state)?;
 
     Ok(Response::new().add_attribute("action", "remove_signers"))
 }
 
+// acknowledge the membership changes of the group, open transactions are evaluated against the
+// members of the group whenever they are voted on or executed
+pub fn member_changed_hook(
+    deps: DepsMut,
+    info: MessageInfo,
+    diffs: Vec<MemberDiff>,
+) -> Result<Response, ContractError> {
+    let curr_state = load_state(deps.as_ref())?;
+    if curr_state.group.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {
+            sender: info.sender,
+        });
+    }
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "member_changed_hook")
+            .add_attribute("changed", diffs.len().to_string())
+    )
+}
+
+// bring the status of an open transaction in line with the current signers and threshold, which
+// may have changed since its last vote. Returns true when the status changed
+fn refresh_transaction(policy: &Policy, state: &State, tx: &mut TxData, block: &BlockInfo) -> bool {
+    let status = state.current_status(tx, block);
+    if status == tx.status {
+        return false;
+    }
+    match status {
+        Some(TxStatus::Passed) => {
+            // the policy may have changed since the transaction was proposed
+            if let Err(err) = check_policy(policy, &tx.msgs) {
+                tx.status = Some(TxStatus::Failed);
+                tx.error = Some(err.to_string());
+            } else {
+                tx.pass(policy.timelock, block);
+            }
+        },
+        status => {
+            tx.status = status;
+            tx.executable_at = None;
+        },
+    }
+    true
+}
+
+// save a transaction whose status was refreshed instead of applying the requested action to it
+fn refreshed_response(
+    deps: DepsMut,
+    tx: &TxData,
+    action: &str,
+) -> Result<Response, ContractError> {
+    TX_EXECUTION.save(deps.storage, tx.id, tx)?;
+
+    Ok(
+        Response::new()
+            .add_attribute("action", action)
+            .add_attribute("tx_id", tx.id.to_string())
+            .add_attribute("status", to_json_string(&tx.status)?)
+    )
+}
+
 pub fn execute_transaction(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
+    title: String,
+    description: String,
     msgs: Vec<CosmosMsg>,
+    expires: Option<Expiration>,
 ) -> Result<Response, ContractError> {
-    let curr_state = STATE.load(deps.storage)?;
+    let mut curr_state = load_state(deps.as_ref())?;
+    load_members(&deps.querier, &mut curr_state, [&info.sender])?;
     if !curr_state.can_execute(info.sender.as_ref()) {
         return Err(ContractError::Unauthorized {
             sender: info.sender,
         });
     }
 
-    let send_msgs: Vec<BankMsg> = msgs.clone().into_iter()
-        .filter_map(|msg| {
-            if let CosmosMsg::Bank(bank_msg) = msg {
-                if let BankMsg::Send { .. } = bank_msg {
-                    Some(bank_msg)
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
-        })
-        .collect();
-    
+    if PAUSE.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::Paused {});
+    }
+
     let policy = POLICY.load(deps.storage)?;
-    
-    for send_msg in send_msgs {
-        if let BankMsg::Send { to_address, amount } = send_msg {
-            if !policy.can_receive(&to_address) {
-                return Err(ContractError::NotAllowedRecipient {
-                    recipient: to_address,
-                });
-            }
+    check_policy(&policy, &msgs)?;
 
-            for amt in amount {
-                if !policy.can_transfer(amt.clone()) {
-                    return Err(ContractError::NotAllowedAmount {
-                        amount: amt,
-                    });
-                }                   
-            }
+    let expires = match (expires, curr_state.default_expiry) {
+        (Some(expires), _) => expires,
+        (None, Some(default_expiry)) => default_expiry.after(&env.block),
+        (None, None) => Expiration::Never {},
+    };
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::InvalidExpiration {});
+    }
+
+    let curr_id = TX_NEXT_ID.load(deps.storage).unwrap_or_default();
+    let mut tx_data = TxData::new(
+        curr_id,
+        msgs,
+        info.sender.clone(),
+        TxStatus::Pending,
+        expires,
+    );
+    tx_data.title = title;
+    tx_data.description = description;
+
+    // bank sends that fit in the allowance of the proposer are dispatched without a vote
+    let mut allowance = ALLOWANCES.may_load(deps.storage, &info.sender)?;
+    let mut allowance_spend = match (&mut allowance, sum_bank_sends(&tx_data.msgs)) {
+        (Some(allowance), Some(amounts)) => {
+            allowance.refresh(&env.block);
+            allowance.can_spend(&amounts).then_some(amounts)
+        },
+        _ => None,
+    };
+    // sends beyond the transfer window of the account fall back to the approval flow
+    if let Some(amounts) = &allowance_spend {
+        match spend_transfer_window(deps.storage, &env.block, amounts) {
+            Ok(()) => {},
+            Err(ContractError::WindowLimitExceeded { .. }) => allowance_spend = None,
+            Err(err) => return Err(err),
         }
     }
 
-    if curr_state.threshold == 1 {
-        let curr_id = TX_NEXT_ID.load(deps.storage).unwrap_or_default();
-        let tx_data = TxData::new(curr_id, msgs.clone(), info.sender.clone(), TxStatus::Done);
-        
-        TX_EXECUTION.save(deps.storage, tx_data.id, &tx_data)?;
-        TX_NEXT_ID.save(deps.storage, &(curr_id + 1))?;
-        
-        Ok(
-            Response::new()
-                .add_messages(msgs.clone())
-                .add_attribute("action", "execute_transaction")
-                .add_attribute("tx_id", curr_id.to_string())
-        )
-    } else if curr_state.threshold > 1 {
-        let curr_id = TX_NEXT_ID.load(deps.storage).unwrap_or_default();
-        let tx_data = TxData::new(curr_id, msgs.clone(), info.sender.clone(), TxStatus::Pending);
-        
-        TX_EXECUTION.save(deps.storage, tx_data.id, &tx_data)?;
-        TX_NEXT_ID.save(deps.storage, &(curr_id + 1))?;
-
-        Ok(
-            Response::new()
-                .add_attribute("action", "execute_transaction")
-                .add_attribute("tx_id", curr_id.to_string())
-        )
-    } else {
-        return Err(ContractError::InvalidThreshold {
-            threshold: curr_state.threshold,
-        });
+    let mut resp = Response::new()
+        .add_attribute("action", "execute_transaction")
+        .add_attribute("tx_id", curr_id.to_string());
+    if let (Some(mut allowance), Some(amounts)) = (allowance, allowance_spend) {
+        allowance.spend(&amounts);
+        ALLOWANCES.save(deps.storage, &info.sender, &allowance)?;
+        tx_data.status = Some(TxStatus::Done);
+        tx_data.from_allowance = true;
+        resp = resp
+            .add_submessage(tx_submessage(&env, &tx_data)?)
+            .add_attribute("allowance_spent", to_json_string(&amounts)?);
+    } else if curr_state.is_passed(&tx_data, &env.block) {
+        tx_data.pass(policy.timelock, &env.block);
     }
+
+    TX_EXECUTION.save(deps.storage, tx_data.id, &tx_data)?;
+    TX_NEXT_ID.save(deps.storage, &(curr_id + 1))?;
+
+    Ok(resp)
 }
 
 pub fn sign_transaction(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     tx_id: u16,
 ) -> Result<Response, ContractError> {
-    let curr_state = STATE.load(deps.storage)?;
+    let mut curr_state = load_state(deps.as_ref())?;
+    load_members(&deps.querier, &mut curr_state, [&info.sender])?;
     if !curr_state.can_execute(info.sender.as_ref()) {
         return Err(ContractError::Unauthorized {
             sender: info.sender,
         });
     }
 
+    if PAUSE.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::Paused {});
+    }
+
     let mut tx = TX_EXECUTION.load(deps.storage, tx_id)?;
+    load_members(&deps.querier, &mut curr_state, tx.voters())?;
+    let policy = POLICY.load(deps.storage)?;
+    if refresh_transaction(&policy, &curr_state, &mut tx, &env.block) {
+        return refreshed_response(deps, &tx, "sign_transaction");
+    }
     if tx.status != Some(TxStatus::Pending) {
         return Err(ContractError::InvalidStatus {
             tx_id,
         });
     }
+    if tx.is_expired(&env.block) {
+        return Err(ContractError::Expired {
+            tx_id,
+        });
+    }
+    if tx.signers.contains(&info.sender) {
+        return Err(ContractError::AlreadySigned {
+            tx_id,
+            signer: info.sender,
+        });
+    }
+    if tx.has_voted(&info.sender) {
+        return Err(ContractError::AlreadyVoted {
+            tx_id,
+            voter: info.sender,
+        });
+    }
 
     tx.signers.push(info.sender.clone());
 
-    let res = Response::new();
-    if is_sufficient_signers(curr_state.threshold, tx.signers.len()) {
-        tx.status = Some(TxStatus::Done);
-        TX_EXECUTION.save(deps.storage, tx.id, &tx)?;
-        Ok(
-            res
-                .add_messages(tx.msgs)
-                .add_attribute("action", "sign_transaction")
-                .add_attribute("tx_id", tx_id.to_string())
-        )
-    } else {
-        TX_EXECUTION.save(deps.storage, tx.id, &tx)?;
-        Ok(
-            res
-                .add_attribute("action", "sign_transaction")
-                .add_attribute("tx_id", tx_id.to_string())
-        )
+    if curr_state.is_passed(&tx, &env.block) {
+        // the policy may have changed since the transaction was proposed
+        if let Err(err) = check_policy(&policy, &tx.msgs) {
+            return fail_transaction(deps, tx, err, "sign_transaction");
+        }
+        tx.pass(policy.timelock, &env.block);
     }
+    TX_EXECUTION.save(deps.storage, tx.id, &tx)?;
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "sign_transaction")
+            .add_attribute("tx_id", tx_id.to_string())
+    )
 }
 
-pub fn set_whitelist_addresses(
+pub fn unsign_transaction(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    addresses: Vec<String>,
+    tx_id: u16,
 ) -> Result<Response, ContractError> {
-    let curr_state = STATE.load(deps.storage)?;
-    if !curr_state.can_modify(info.sender.as_ref()) {
+    let mut curr_state = load_state(deps.as_ref())?;
+    load_members(&deps.querier, &mut curr_state, [&info.sender])?;
+    if !curr_state.can_execute(info.sender.as_ref()) {
         return Err(ContractError::Unauthorized {
             sender: info.sender,
         });
     }
-    
-    let addresses = map_validate(deps.api, &addresses)?;
-    let mut curr_policy = POLICY.load(deps.storage)?;
 
-    let mut unique_addresses: HashSet<Addr> = curr_policy.whitelist_addresses.into_iter().collect();
-    unique_addresses.extend(addresses);
-    
-    curr_policy.whitelist_addresses = unique_addresses.into_iter().collect();
-    POLICY.save(deps.storage, &curr_policy)?;
+    let mut tx = TX_EXECUTION.load(deps.storage, tx_id)?;
+    load_members(&deps.querier, &mut curr_state, tx.voters())?;
+    let policy = POLICY.load(deps.storage)?;
+    if refresh_transaction(&policy, &curr_state, &mut tx, &env.block) {
+        return refreshed_response(deps, &tx, "unsign_transaction");
+    }
+    if tx.status != Some(TxStatus::Pending) {
+        return Err(ContractError::InvalidStatus {
+            tx_id,
+        });
+    }
+    if tx.is_expired(&env.block) {
+        return Err(ContractError::Expired {
+            tx_id,
+        });
+    }
+    if !tx.signers.contains(&info.sender) {
+        return Err(ContractError::NotSigned {
+            tx_id,
+            signer: info.sender,
+        });
+    }
 
-    Ok(Response::new().add_attribute("action", "add_whitelist_addresses"))
+    tx.signers.retain(|signer| signer != info.sender);
+    TX_EXECUTION.save(deps.storage, tx.id, &tx)?;
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "unsign_transaction")
+            .add_attribute("tx_id", tx_id.to_string())
+    )
 }
 
-pub fn remove_whitelist_addresses (
+pub fn reject_transaction(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    addresses: Vec<String>,
+    tx_id: u16,
 ) -> Result<Response, ContractError> {
-    let curr_state = STATE.load(deps.storage)?;
-    if !curr_state.can_modify(info.sender.as_ref()) {
+    let mut curr_state = load_state(deps.as_ref())?;
+    load_members(&deps.querier, &mut curr_state, [&info.sender])?;
+    if !curr_state.can_execute(info.sender.as_ref()) {
         return Err(ContractError::Unauthorized {
             sender: info.sender,
         });
     }
 
-    let addresses = map_validate(deps.api, &addresses)?;
-    let mut curr_policy = POLICY.load(deps.storage)?;
+    let mut tx = TX_EXECUTION.load(deps.storage, tx_id)?;
+    load_members(&deps.querier, &mut curr_state, tx.voters())?;
+    let policy = POLICY.load(deps.storage)?;
+    if refresh_transaction(&policy, &curr_state, &mut tx, &env.block) {
+        return refreshed_response(deps, &tx, "reject_transaction");
+    }
+    if tx.status != Some(TxStatus::Pending) {
+        return Err(ContractError::InvalidStatus {
+            tx_id,
+        });
+    }
+    if tx.is_expired(&env.block) {
+        return Err(ContractError::Expired {
+            tx_id,
+        });
+    }
+    if tx.has_voted(&info.sender) {
+        return Err(ContractError::AlreadyVoted {
+            tx_id,
+            voter: info.sender,
+        });
+    }
 
-    curr_policy.whitelist_addresses.retain(|curr_whitelist| !addresses.contains(curr_whitelist));
-    POLICY.save(deps.storage, &curr_policy)?;
+    tx.rejections.push(info.sender);
 
-    Ok(Response::new().add_attribute("action", "remove_whitelist_addresses"))
+    // the transaction is rejected once the signers who did not reject can't reach the threshold
+    if curr_state.is_rejected(&tx) {
+        tx.status = Some(TxStatus::Rejected);
+    }
+    TX_EXECUTION.save(deps.storage, tx.id, &tx)?;
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "reject_transaction")
+            .add_attribute("tx_id", tx_id.to_string())
+    )
 }
 
-pub fn set_transfer_limits(
+// record an abstention, which counts towards the quorum without supporting the transaction
+fn abstain_transaction(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    coins: Vec<Coin>,
+    tx_id: u16,
 ) -> Result<Response, ContractError> {
-    let curr_state = STATE.load(deps.storage)?;
-    if !curr_state.can_modify(info.sender.as_ref()) {
+    let mut curr_state = load_state(deps.as_ref())?;
+    load_members(&deps.querier, &mut curr_state, [&info.sender])?;
+    if !curr_state.can_execute(info.sender.as_ref()) {
         return Err(ContractError::Unauthorized {
             sender: info.sender,
         });
     }
-    
-    let mut curr_policy = POLICY.load(deps.storage)?;
 
-    // Convert current transfer limits to a HashMap for easy updating
-    let mut transfer_limits_map: HashMap<String, Uint128> = HashMap::new();
-    for coin in curr_policy.transfer_limits {
-        transfer_limits_map.insert(coin.denom.clone(), coin.amount);
+    let mut tx = TX_EXECUTION.load(deps.storage, tx_id)?;
+    load_members(&deps.querier, &mut curr_state, tx.voters())?;
+    let policy = POLICY.load(deps.storage)?;
+    if refresh_transaction(&policy, &curr_state, &mut tx, &env.block) {
+        return refreshed_response(deps, &tx, "abstain_transaction");
     }
-    
-    // Update the transfer limits with the new coins
-    for coin in coins.clone() {
-        transfer_limits_map.insert(coin.denom.clone(), coin.amount);
+    if tx.status != Some(TxStatus::Pending) {
+        return Err(ContractError::InvalidStatus {
+            tx_id,
+        });
+    }
+    if tx.is_expired(&env.block) {
+        return Err(ContractError::Expired {
+            tx_id,
+        });
+    }
+    if tx.has_voted(&info.sender) {
+        return Err(ContractError::AlreadyVoted {
+            tx_id,
+            voter: info.sender,
+        });
     }
-    
-    // Convert the HashMap back to a Vec<Coin>
-    curr_policy.transfer_limits = transfer_limits_map.into_iter()
-        .map(|(denom, amount)| Coin { denom, amount })
-        .collect();
 
-    POLICY.save(deps.storage, &curr_policy)?;
+    tx.abstentions.push(info.sender);
 
-    Ok(Response::new().add_attribute("action", "set_transfer_limits"))
+    // an abstention may complete the quorum of the approvals or take away the last chance to pass
+    if curr_state.is_passed(&tx, &env.block) {
+        if let Err(err) = check_policy(&policy, &tx.msgs) {
+            return fail_transaction(deps, tx, err, "abstain_transaction");
+        }
+        tx.pass(policy.timelock, &env.block);
+    } else if curr_state.is_rejected(&tx) {
+        tx.status = Some(TxStatus::Rejected);
+    }
+    TX_EXECUTION.save(deps.storage, tx.id, &tx)?;
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "abstain_transaction")
+            .add_attribute("tx_id", tx_id.to_string())
+    )
 }
 
-pub fn remove_transfer_limits(
+// cast a cw3 vote on the transaction
+pub fn vote(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    denoms: Vec<String>,
+    tx_id: u16,
+    vote: Vote,
 ) -> Result<Response, ContractError> {
-    let curr_state = STATE.load(deps.storage)?;
-    if !curr_state.can_modify(info.sender.as_ref()) {
-        return Err(ContractError::Unauthorized {
-            sender: info.sender,
-        });
+    match vote {
+        Vote::Yes => sign_transaction(deps, env, info, tx_id),
+        Vote::No | Vote::Veto => reject_transaction(deps, env, info, tx_id),
+        Vote::Abstain => abstain_transaction(deps, env, info, tx_id),
     }
-    
-    let mut curr_policy = POLICY.load(deps.storage)?;
-    
+}
+
+pub fn close_transaction(
+    deps: DepsMut,
+    env: Env,
+    tx_id: u16,
+) -> Result<Response, ContractError> {
+    let mut curr_state = load_state(deps.as_ref())?;
+    let mut tx = TX_EXECUTION.load(deps.storage, tx_id)?;
+    load_members(&deps.querier, &mut curr_state, tx.voters())?;
+    if tx.status != Some(TxStatus::Pending) {
+        return Err(ContractError::InvalidStatus {
+            tx_id,
+        });
+    }
+    if !tx.is_expired(&env.block) {
+        return Err(ContractError::NotExpired {
+            tx_id,
+        });
+    }
+
+    // a quorum threshold may still be passed by the votes cast before the expiry
+    if curr_state.is_passed(&tx, &env.block) {
+        let policy = POLICY.load(deps.storage)?;
+        if let Err(err) = check_policy(&policy, &tx.msgs) {
+            return fail_transaction(deps, tx, err, "close_transaction");
+        }
+        tx.pass(policy.timelock, &env.block);
+    } else {
+        tx.update_status(&env.block);
+    }
+    TX_EXECUTION.save(deps.storage, tx.id, &tx)?;
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "close_transaction")
+            .add_attribute("tx_id", tx_id.to_string())
+    )
+}
+
+pub fn cancel_transaction(
+    deps: DepsMut,
+    info: MessageInfo,
+    tx_id: u16,
+) -> Result<Response, ContractError> {
+    let curr_state = load_state(deps.as_ref())?;
+    let mut tx = TX_EXECUTION.load(deps.storage, tx_id)?;
+    if tx.proposer != info.sender
+        && !has_permission(deps.storage, &curr_state, &info.sender, Permission::CancelTransaction)?
+    {
+        return Err(ContractError::MissingPermission {
+            sender: info.sender,
+            permission: Permission::CancelTransaction,
+        });
+    }
+    // passed transactions stay cancellable while they wait for execution
+    if tx.status != Some(TxStatus::Pending) && tx.status != Some(TxStatus::Passed) {
+        return Err(ContractError::InvalidStatus {
+            tx_id,
+        });
+    }
+
+    tx.status = Some(TxStatus::Cancelled);
+    TX_EXECUTION.save(deps.storage, tx.id, &tx)?;
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "cancel_transaction")
+            .add_attribute("tx_id", tx_id.to_string())
+            .add_attribute("cancelled_by", info.sender)
+    )
+}
+
+pub fn execute_approved(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    tx_id: u16,
+) -> Result<Response, ContractError> {
+    let mut curr_state = load_state(deps.as_ref())?;
+    load_members(&deps.querier, &mut curr_state, [&info.sender])?;
+    if !curr_state.can_execute_approved(info.sender.as_ref())
+        && !has_permission(deps.storage, &curr_state, &info.sender, Permission::ExecuteApproved)?
+    {
+        return Err(ContractError::MissingPermission {
+            sender: info.sender,
+            permission: Permission::ExecuteApproved,
+        });
+    }
+
+    if PAUSE.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::Paused {});
+    }
+
+    let mut tx = TX_EXECUTION.load(deps.storage, tx_id)?;
+    // the signers may have changed since the transaction passed, it goes back to voting when the
+    // approvals of the current signers no longer reach the threshold
+    load_members(&deps.querier, &mut curr_state, tx.voters())?;
+    let policy = POLICY.load(deps.storage)?;
+    if refresh_transaction(&policy, &curr_state, &mut tx, &env.block) && !tx.is_executable(&env.block) {
+        return refreshed_response(deps, &tx, "execute_approved");
+    }
+    if tx.status != Some(TxStatus::Passed) {
+        return Err(ContractError::InvalidStatus {
+            tx_id,
+        });
+    }
+    if !tx.is_executable(&env.block) {
+        return Err(ContractError::Timelocked {
+            tx_id,
+        });
+    }
+    if let Err(err) = check_policy(&policy, &tx.msgs) {
+        return fail_transaction(deps, tx, err, "execute_approved");
+    }
+    spend_transfer_window(deps.storage, &env.block, &total_outflows(&tx.msgs))?;
+
+    tx.status = Some(TxStatus::Done);
+    TX_EXECUTION.save(deps.storage, tx.id, &tx)?;
+
+    Ok(
+        Response::new()
+            .add_submessage(tx_submessage(&env, &tx)?)
+            .add_attribute("action", "execute_approved")
+            .add_attribute("tx_id", tx_id.to_string())
+    )
+}
+
+// record the outflow in the transfer window of the account, refusing it when it exceeds the window limits
+fn spend_transfer_window(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    outflows: &[Coin],
+) -> Result<(), ContractError> {
+    let Some(mut window) = TRANSFER_WINDOW.may_load(storage)? else {
+        return Ok(());
+    };
+    window.refresh(block);
+    if let Some(amount) = window.exceeded_by(outflows) {
+        return Err(ContractError::WindowLimitExceeded { amount });
+    }
+    window.spend(outflows);
+    TRANSFER_WINDOW.save(storage, &window)?;
+    Ok(())
+}
+
+// give the outflow back to the transfer window, and to the allowance of the proposer when the
+// transaction was dispatched from it
+fn refund_outflows(
+    storage: &mut dyn Storage,
+    tx: &TxData,
+    outflows: &[Coin],
+) -> StdResult<()> {
+    if let Some(mut window) = TRANSFER_WINDOW.may_load(storage)? {
+        window.refund(outflows);
+        TRANSFER_WINDOW.save(storage, &window)?;
+    }
+    if tx.from_allowance {
+        if let Some(mut allowance) = ALLOWANCES.may_load(storage, &tx.proposer)? {
+            allowance.refund(outflows);
+            ALLOWANCES.save(storage, &tx.proposer, &allowance)?;
+        }
+    }
+    Ok(())
+}
+
+// close the transaction as failed without dispatching it, keeping the reason on the transaction
+fn fail_transaction(
+    deps: DepsMut,
+    mut tx: TxData,
+    err: ContractError,
+    action: &str,
+) -> Result<Response, ContractError> {
+    tx.status = Some(TxStatus::Failed);
+    tx.error = Some(err.to_string());
+    TX_EXECUTION.save(deps.storage, tx.id, &tx)?;
+
+    Ok(
+        Response::new()
+            .add_attribute("action", action)
+            .add_attribute("tx_id", tx.id.to_string())
+            .add_attribute("status", "failed")
+            .add_attribute("error", err.to_string())
+    )
+}
+
+// dispatch the transaction through a call of the account to itself, so its messages succeed or
+// revert together and the outcome is recorded by tx_reply
+fn tx_submessage(env: &Env, tx: &TxData) -> StdResult<SubMsg> {
+    let dispatch = WasmMsg::Execute {
+        contract_addr: env.contract.address.to_string(),
+        msg: to_json_binary(&ExecuteMsg::<Empty>::DispatchTransaction { tx_id: tx.id })?,
+        funds: vec![],
+    };
+    Ok(SubMsg::reply_always(dispatch, to_reply_id(tx.id)))
+}
+
+pub fn dispatch_transaction(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    tx_id: u16,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized { sender: info.sender });
+    }
+
+    let tx = TX_EXECUTION.load(deps.storage, tx_id)?;
+    if tx.status != Some(TxStatus::Done) {
+        return Err(ContractError::InvalidStatus {
+            tx_id,
+        });
+    }
+
+    Ok(
+        Response::new()
+            .add_messages(tx.msgs)
+            .add_attribute("action", "dispatch_transaction")
+            .add_attribute("tx_id", tx_id.to_string())
+    )
+}
+
+pub fn tx_reply(
+    deps: DepsMut,
+    msg: Reply,
+) -> Result<Response, ContractError> {
+    let tx_id = from_reply_id(msg.id);
+    let mut tx = TX_EXECUTION.load(deps.storage, tx_id)?;
+
+    let success = msg.result.is_ok();
+    if let SubMsgResult::Err(err) = msg.result {
+        tx.status = Some(TxStatus::Failed);
+        tx.error = Some(err);
+        // none of the messages took effect, so their funds no longer count against the limits
+        refund_outflows(deps.storage, &tx, &total_outflows(&tx.msgs))?;
+    }
+    tx.results = (0..tx.msgs.len() as u32)
+        .map(|msg_index| MsgResult {
+            msg_index,
+            success,
+            error: None,
+        })
+        .collect();
+    TX_EXECUTION.save(deps.storage, tx.id, &tx)?;
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "tx_reply")
+            .add_attribute("tx_id", tx_id.to_string())
+            .add_attribute("success", success.to_string())
+    )
+}
+
+pub fn set_whitelist_addresses(
+    deps: DepsMut,
+    info: MessageInfo,
+    addresses: Vec<String>,
+) -> Result<Response, ContractError> {
+    let curr_state = load_state(deps.as_ref())?;
+    if !has_permission(deps.storage, &curr_state, &info.sender, Permission::ManagePolicy)? {
+        return Err(ContractError::MissingPermission {
+            sender: info.sender,
+            permission: Permission::ManagePolicy,
+        });
+    }
+    
+    let addresses = map_validate(deps.api, &addresses)?;
+    let mut curr_policy = POLICY.load(deps.storage)?;
+
+    let mut unique_addresses: HashSet<Addr> = curr_policy.whitelist_addresses.into_iter().collect();
+    unique_addresses.extend(addresses);
+    
+    curr_policy.whitelist_addresses = unique_addresses.into_iter().collect();
+    POLICY.save(deps.storage, &curr_policy)?;
+
+    Ok(Response::new().add_attribute("action", "add_whitelist_addresses"))
+}
+
+pub fn remove_whitelist_addresses (
+    deps: DepsMut,
+    info: MessageInfo,
+    addresses: Vec<String>,
+) -> Result<Response, ContractError> {
+    let curr_state = load_state(deps.as_ref())?;
+    if !has_permission(deps.storage, &curr_state, &info.sender, Permission::ManagePolicy)? {
+        return Err(ContractError::MissingPermission {
+            sender: info.sender,
+            permission: Permission::ManagePolicy,
+        });
+    }
+
+    let addresses = map_validate(deps.api, &addresses)?;
+    let mut curr_policy = POLICY.load(deps.storage)?;
+
+    curr_policy.whitelist_addresses.retain(|curr_whitelist| !addresses.contains(curr_whitelist));
+    POLICY.save(deps.storage, &curr_policy)?;
+
+    Ok(Response::new().add_attribute("action", "remove_whitelist_addresses"))
+}
+
+pub fn set_transfer_limits(
+    deps: DepsMut,
+    info: MessageInfo,
+    coins: Vec<Coin>,
+) -> Result<Response, ContractError> {
+    let curr_state = load_state(deps.as_ref())?;
+    if !has_permission(deps.storage, &curr_state, &info.sender, Permission::ManagePolicy)? {
+        return Err(ContractError::MissingPermission {
+            sender: info.sender,
+            permission: Permission::ManagePolicy,
+        });
+    }
+    
+    let mut curr_policy = POLICY.load(deps.storage)?;
+
+    // Convert current transfer limits to a HashMap for easy updating
+    let mut transfer_limits_map: HashMap<String, Uint128> = HashMap::new();
+    for coin in curr_policy.transfer_limits {
+        transfer_limits_map.insert(coin.denom.clone(), coin.amount);
+    }
+    
+    // Update the transfer limits with the new coins
+    for coin in coins.clone() {
+        transfer_limits_map.insert(coin.denom.clone(), coin.amount);
+    }
+    
+    // Convert the HashMap back to a Vec<Coin>
+    curr_policy.transfer_limits = transfer_limits_map.into_iter()
+        .map(|(denom, amount)| Coin { denom, amount })
+        .collect();
+
+    POLICY.save(deps.storage, &curr_policy)?;
+
+    Ok(Response::new().add_attribute("action", "set_transfer_limits"))
+}
+
+pub fn remove_transfer_limits(
+    deps: DepsMut,
+    info: MessageInfo,
+    denoms: Vec<String>,
+) -> Result<Response, ContractError> {
+    let curr_state = load_state(deps.as_ref())?;
+    if !has_permission(deps.storage, &curr_state, &info.sender, Permission::ManagePolicy)? {
+        return Err(ContractError::MissingPermission {
+            sender: info.sender,
+            permission: Permission::ManagePolicy,
+        });
+    }
+    
+    let mut curr_policy = POLICY.load(deps.storage)?;
+    
     // Convert the list of denominations to a HashSet for efficient lookup
     let denoms_set: HashSet<String> = denoms.into_iter().collect();
     
@@ -344,4 +1020,390 @@ pub fn remove_transfer_limits(
     POLICY.save(deps.storage, &curr_policy)?;
     
     Ok(Response::new().add_attribute("action", "remove_transfer_limits"))
-}
\ No newline at end of file
+}
+
+pub fn set_timelock(
+    deps: DepsMut,
+    info: MessageInfo,
+    timelock: Option<Duration>,
+) -> Result<Response, ContractError> {
+    let curr_state = load_state(deps.as_ref())?;
+    if !has_permission(deps.storage, &curr_state, &info.sender, Permission::ManagePolicy)? {
+        return Err(ContractError::MissingPermission {
+            sender: info.sender,
+            permission: Permission::ManagePolicy,
+        });
+    }
+
+    let mut curr_policy = POLICY.load(deps.storage)?;
+    curr_policy.timelock = timelock;
+    POLICY.save(deps.storage, &curr_policy)?;
+
+    Ok(Response::new().add_attribute("action", "set_timelock"))
+}
+
+pub fn set_allowance(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    signer: String,
+    limits: Vec<Coin>,
+    period: Duration,
+) -> Result<Response, ContractError> {
+    let curr_state = load_state(deps.as_ref())?;
+    if !has_permission(deps.storage, &curr_state, &info.sender, Permission::ManagePolicy)? {
+        return Err(ContractError::MissingPermission {
+            sender: info.sender,
+            permission: Permission::ManagePolicy,
+        });
+    }
+
+    let signer = validate_addr(deps.api, &signer)?;
+    let allowance = SpendWindow::new(limits, period, &env.block);
+    ALLOWANCES.save(deps.storage, &signer, &allowance)?;
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "set_allowance")
+            .add_attribute("signer", signer)
+    )
+}
+
+pub fn remove_allowance(
+    deps: DepsMut,
+    info: MessageInfo,
+    signer: String,
+) -> Result<Response, ContractError> {
+    let curr_state = load_state(deps.as_ref())?;
+    if !has_permission(deps.storage, &curr_state, &info.sender, Permission::ManagePolicy)? {
+        return Err(ContractError::MissingPermission {
+            sender: info.sender,
+            permission: Permission::ManagePolicy,
+        });
+    }
+
+    let signer = validate_addr(deps.api, &signer)?;
+    if ALLOWANCES.may_load(deps.storage, &signer)?.is_none() {
+        return Err(ContractError::NoAllowance { signer });
+    }
+    ALLOWANCES.remove(deps.storage, &signer);
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "remove_allowance")
+            .add_attribute("signer", signer)
+    )
+}
+
+pub fn set_transfer_window(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    limits: Vec<Coin>,
+    period: Duration,
+) -> Result<Response, ContractError> {
+    let curr_state = load_state(deps.as_ref())?;
+    if !has_permission(deps.storage, &curr_state, &info.sender, Permission::ManagePolicy)? {
+        return Err(ContractError::MissingPermission {
+            sender: info.sender,
+            permission: Permission::ManagePolicy,
+        });
+    }
+
+    // changing the limits keeps what was already sent in the current period
+    let window = match TRANSFER_WINDOW.may_load(deps.storage)? {
+        Some(window) => SpendWindow { limits, period, ..window },
+        None => SpendWindow::new(limits, period, &env.block),
+    };
+    TRANSFER_WINDOW.save(deps.storage, &window)?;
+
+    Ok(Response::new().add_attribute("action", "set_transfer_window"))
+}
+
+pub fn remove_transfer_window(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let curr_state = load_state(deps.as_ref())?;
+    if !has_permission(deps.storage, &curr_state, &info.sender, Permission::ManagePolicy)? {
+        return Err(ContractError::MissingPermission {
+            sender: info.sender,
+            permission: Permission::ManagePolicy,
+        });
+    }
+
+    TRANSFER_WINDOW.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "remove_transfer_window"))
+}
+
+pub fn set_allowed_msg_types(
+    deps: DepsMut,
+    info: MessageInfo,
+    msg_types: Vec<MsgType>,
+) -> Result<Response, ContractError> {
+    let curr_state = load_state(deps.as_ref())?;
+    if !has_permission(deps.storage, &curr_state, &info.sender, Permission::ManagePolicy)? {
+        return Err(ContractError::MissingPermission {
+            sender: info.sender,
+            permission: Permission::ManagePolicy,
+        });
+    }
+
+    // a self governed account changes its configuration by calling itself
+    if curr_state.self_governed && !msg_types.contains(&MsgType::WasmExecute) {
+        return Err(ContractError::SelfGovernanceLocked {});
+    }
+
+    let mut curr_policy = POLICY.load(deps.storage)?;
+    curr_policy.allowed_msg_types = vec![];
+    for msg_type in msg_types {
+        if !curr_policy.allowed_msg_types.contains(&msg_type) {
+            curr_policy.allowed_msg_types.push(msg_type);
+        }
+    }
+    POLICY.save(deps.storage, &curr_policy)?;
+
+    Ok(Response::new().add_attribute("action", "set_allowed_msg_types"))
+}
+
+pub fn set_recovery_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: Option<RecoveryConfig>,
+) -> Result<Response, ContractError> {
+    let mut curr_state = load_state(deps.as_ref())?;
+    if !curr_state.can_modify(info.sender.as_ref()) {
+        return Err(ContractError::Unauthorized {
+            sender: info.sender,
+        });
+    }
+
+    if config.as_ref().is_some_and(|c| c.threshold == 0) {
+        return Err(ContractError::InvalidRecoveryThreshold {});
+    }
+
+    // disabling recovery also drops the recovery in progress
+    if config.is_none() {
+        RECOVERY.remove(deps.storage);
+    }
+    curr_state.recovery_config = config;
+    STATE.save(deps.storage, &curr_state)?;
+
+    Ok(Response::new().add_attribute("action", "set_recovery_config"))
+}
+
+pub fn propose_recovery(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    signers: Vec<Signer>,
+    threshold: Threshold,
+) -> Result<Response, ContractError> {
+    let curr_state = load_state(deps.as_ref())?;
+    let config = curr_state.recovery_config.clone().ok_or(ContractError::RecoveryDisabled {})?;
+    if !is_guardian(deps.storage, &info.sender)? {
+        return Err(ContractError::NotGuardian {
+            sender: info.sender,
+        });
+    }
+    if RECOVERY.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::RecoveryInProgress {});
+    }
+
+    // validate the new signers the same way they would be set on the account
+    let mut recovered_state = State {
+        signers: vec![],
+        threshold,
+        group: None,
+        ..curr_state
+    };
+    recovered_state.upsert_signers(map_validate_signers(deps.api, &signers)?);
+    recovered_state.validate_threshold(&recovered_state.threshold)?;
+
+    let mut recovery = Recovery {
+        proposer: info.sender.clone(),
+        signers: recovered_state.signers,
+        threshold: recovered_state.threshold,
+        approvals: vec![info.sender.clone()],
+        executable_at: None,
+    };
+    if recovery.approvals.len() as u64 >= config.threshold {
+        recovery.executable_at = Some(config.delay.after(&env.block));
+    }
+    RECOVERY.save(deps.storage, &recovery)?;
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "propose_recovery")
+            .add_attribute("proposer", info.sender)
+    )
+}
+
+pub fn approve_recovery(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let curr_state = load_state(deps.as_ref())?;
+    let config = curr_state.recovery_config.ok_or(ContractError::RecoveryDisabled {})?;
+    if !is_guardian(deps.storage, &info.sender)? {
+        return Err(ContractError::NotGuardian {
+            sender: info.sender,
+        });
+    }
+
+    let mut recovery = RECOVERY
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoRecovery {})?;
+    if recovery.approvals.contains(&info.sender) {
+        return Err(ContractError::RecoveryAlreadyApproved {
+            guardian: info.sender,
+        });
+    }
+
+    recovery.approvals.push(info.sender.clone());
+    if recovery.executable_at.is_none()
+        && guardian_approvals(deps.storage, &recovery)? >= config.threshold
+    {
+        recovery.executable_at = Some(config.delay.after(&env.block));
+    }
+    RECOVERY.save(deps.storage, &recovery)?;
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "approve_recovery")
+            .add_attribute("guardian", info.sender)
+    )
+}
+
+pub fn veto_recovery(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut curr_state = load_state(deps.as_ref())?;
+    load_members(&deps.querier, &mut curr_state, [&info.sender])?;
+    if !curr_state.is_signer(&info.sender) {
+        return Err(ContractError::Unauthorized {
+            sender: info.sender,
+        });
+    }
+    if RECOVERY.may_load(deps.storage)?.is_none() {
+        return Err(ContractError::NoRecovery {});
+    }
+    RECOVERY.remove(deps.storage);
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "veto_recovery")
+            .add_attribute("signer", info.sender)
+    )
+}
+
+pub fn execute_recovery(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    if !is_guardian(deps.storage, &info.sender)? {
+        return Err(ContractError::NotGuardian {
+            sender: info.sender,
+        });
+    }
+
+    let mut curr_state = load_state(deps.as_ref())?;
+    let config = curr_state.recovery_config.as_ref().ok_or(ContractError::RecoveryDisabled {})?;
+    let recovery = RECOVERY
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoRecovery {})?;
+    // guardians may have been revoked or the config tightened since the recovery was approved
+    if !recovery.is_executable(&env.block)
+        || guardian_approvals(deps.storage, &recovery)? < config.threshold
+    {
+        return Err(ContractError::RecoveryNotReady {});
+    }
+
+    // the recovered signers replace the group, which may be the part that was lost
+    curr_state.signers = recovery.signers;
+    curr_state.threshold = recovery.threshold;
+    curr_state.group = None;
+    STATE.save(deps.storage, &curr_state)?;
+    RECOVERY.remove(deps.storage);
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "execute_recovery")
+    )
+}
+
+pub fn pause(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut curr_state = load_state(deps.as_ref())?;
+    load_members(&deps.querier, &mut curr_state, [&info.sender])?;
+    if !curr_state.is_signer(&info.sender) && !is_guardian(deps.storage, &info.sender)? {
+        return Err(ContractError::Unauthorized {
+            sender: info.sender,
+        });
+    }
+    if PAUSE.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::Paused {});
+    }
+
+    let pause = Pause {
+        paused_by: info.sender.clone(),
+        paused_at_height: env.block.height,
+        paused_at_time: env.block.time,
+        unpause_votes: vec![],
+    };
+    PAUSE.save(deps.storage, &pause)?;
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "pause")
+            .add_attribute("paused_by", info.sender)
+    )
+}
+
+pub fn unpause(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut curr_state = load_state(deps.as_ref())?;
+    load_members(&deps.querier, &mut curr_state, [&info.sender])?;
+    let is_admin = curr_state.can_modify(info.sender.as_ref());
+    if !is_admin && !curr_state.is_signer(&info.sender) {
+        return Err(ContractError::Unauthorized {
+            sender: info.sender,
+        });
+    }
+
+    let mut pause = PAUSE
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NotPaused {})?;
+    if !is_admin {
+        if pause.unpause_votes.contains(&info.sender) {
+            return Err(ContractError::AlreadyVotedUnpause {
+                voter: info.sender,
+            });
+        }
+        pause.unpause_votes.push(info.sender.clone());
+    }
+    load_members(&deps.querier, &mut curr_state, &pause.unpause_votes)?;
+
+    let paused = !is_admin && !curr_state.is_unpaused(&pause);
+    if paused {
+        PAUSE.save(deps.storage, &pause)?;
+    } else {
+        PAUSE.remove(deps.storage);
+    }
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "unpause")
+            .add_attribute("sender", info.sender)
+            .add_attribute("paused", paused.to_string())
+    )
+}
@@ -1,17 +1,334 @@
-use cosmwasm_std::{StdResult, Api, Addr};
+use cosmwasm_std::{
+    from_json, StdError, StdResult, Api, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps,
+    DistributionMsg, QuerierWrapper, StakingMsg, Storage, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use cw_utils::Threshold;
+
+use crate::cw4::{Cw4QueryMsg, MemberListResponse, MemberResponse, TotalWeightResponse};
+use crate::error::ContractError;
+use crate::msg::Signer;
+use crate::state::{MsgType, Permission, Policy, Recovery, Role, SignerData, State, ROLES, STATE};
 
 pub fn map_validate(api: &dyn Api, addresses: &[String]) -> StdResult<Vec<Addr>> {
     addresses.iter().map(|addr| api.addr_validate(addr)).collect()
 }
 
-pub fn validate_addr(api: &dyn Api, address: &String) -> StdResult<Addr> {
+// a zero weight signer could still sign and vote, so every signer must carry some weight
+pub fn map_validate_signers(
+    api: &dyn Api,
+    signers: &[Signer],
+) -> Result<Vec<SignerData>, ContractError> {
+    signers
+        .iter()
+        .map(|s| {
+            let addr = api.addr_validate(&s.addr)?;
+            if s.weight == 0 {
+                return Err(ContractError::ZeroWeight { signer: addr });
+            }
+            Ok(SignerData { addr, weight: s.weight })
+        })
+        .collect()
+}
+
+pub fn validate_addr(api: &dyn Api, address: &str) -> StdResult<Addr> {
     api.addr_validate(address)
 }
 
-pub fn is_valid_threshold(threshold: u8, len_signers: usize) -> bool {
-    threshold > 0 && threshold <= len_signers.try_into().unwrap()
+// return true if the address is the admin or holds a role granting the permission. A self governed
+// account only accepts configuration changes from itself, through an approved transaction
+pub fn has_permission(
+    storage: &dyn Storage,
+    state: &State,
+    addr: &Addr,
+    permission: Permission,
+) -> StdResult<bool> {
+    if state.is_admin(addr) {
+        return Ok(true);
+    }
+    if state.self_governed && permission.is_config() {
+        return Ok(false);
+    }
+    let roles = ROLES.may_load(storage, addr)?.unwrap_or_default();
+    Ok(roles.iter().any(|role| role.has_permission(permission)))
+}
+
+// page size used to read the members of a cw4 group
+const GROUP_PAGE_LIMIT: u32 = 30;
+
+// read every member of the cw4 group as a signer, members without weight can't sign
+pub fn query_group_signers(querier: &QuerierWrapper, group: &Addr) -> StdResult<Vec<SignerData>> {
+    let mut signers = vec![];
+    let mut start_after = None;
+    loop {
+        let resp: MemberListResponse = querier.query_wasm_smart(
+            group,
+            &Cw4QueryMsg::ListMembers {
+                start_after: start_after.take(),
+                limit: Some(GROUP_PAGE_LIMIT),
+            },
+        )?;
+        let count = resp.members.len();
+        start_after = resp.members.last().map(|m| m.addr.clone());
+        signers.extend(
+            resp.members
+                .into_iter()
+                .filter(|m| m.weight > 0)
+                .map(|m| SignerData { addr: Addr::unchecked(m.addr), weight: m.weight }),
+        );
+        if count < GROUP_PAGE_LIMIT as usize {
+            return Ok(signers);
+        }
+    }
+}
+
+// read the total weight of the cw4 group
+pub fn query_group_weight(querier: &QuerierWrapper, group: &Addr) -> StdResult<u64> {
+    let resp: TotalWeightResponse = querier.query_wasm_smart(group, &Cw4QueryMsg::TotalWeight {})?;
+    Ok(resp.weight)
+}
+
+// load the state. For an account backed by a cw4 group only the total weight is read here, the
+// members a handler needs are read with load_members
+pub fn load_state(deps: Deps) -> StdResult<State> {
+    let mut state = STATE.load(deps.storage)?;
+    if let Some(group) = &state.group {
+        state.signers = vec![];
+        state.group_weight = query_group_weight(&deps.querier, group)?;
+    }
+    Ok(state)
+}
+
+// load the state with every signer, which reads the full member list of the cw4 group
+pub fn load_state_with_signers(deps: Deps) -> StdResult<State> {
+    let mut state = load_state(deps)?;
+    if let Some(group) = &state.group {
+        state.signers = query_group_signers(&deps.querier, group)?;
+    }
+    Ok(state)
+}
+
+// read the given addresses from the cw4 group, so the state knows which of them are signers
+pub fn load_members<'a>(
+    querier: &QuerierWrapper,
+    state: &mut State,
+    addrs: impl IntoIterator<Item = &'a Addr>,
+) -> StdResult<()> {
+    let Some(group) = state.group.clone() else {
+        return Ok(());
+    };
+    for addr in addrs {
+        if state.is_signer(addr) {
+            continue;
+        }
+        let resp: MemberResponse = querier.query_wasm_smart(
+            &group,
+            &Cw4QueryMsg::Member {
+                addr: addr.to_string(),
+                at_height: None,
+            },
+        )?;
+        if let Some(weight) = resp.weight.filter(|weight| *weight > 0) {
+            state.signers.push(SignerData { addr: addr.clone(), weight });
+        }
+    }
+    Ok(())
+}
+
+// return true if the address holds the guardian role
+pub fn is_guardian(storage: &dyn Storage, addr: &Addr) -> StdResult<bool> {
+    let roles = ROLES.may_load(storage, addr)?.unwrap_or_default();
+    Ok(roles.contains(&Role::Guardian))
+}
+
+// return the number of recovery approvals from addresses that still hold the guardian role
+pub fn guardian_approvals(storage: &dyn Storage, recovery: &Recovery) -> StdResult<u64> {
+    let mut count = 0;
+    for approver in &recovery.approvals {
+        if is_guardian(storage, approver)? {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+// factor used to round up the weight needed for percentage thresholds
+const PRECISION_FACTOR: u128 = 1_000_000_000;
+
+// return the weight needed to reach the percentage of the given weight, rounded up
+pub fn votes_needed(weight: u64, percentage: Decimal) -> u64 {
+    let applied = Uint128::new(PRECISION_FACTOR * weight as u128).mul_floor(percentage);
+    applied.u128().div_ceil(PRECISION_FACTOR) as u64
+}
+
+// return true if the approving weight passes the threshold, evaluated against the total weight of the signers.
+// as in cw3, the threshold of a quorum is measured against the weight that did not abstain, which once
+// expired is only the weight that voted
+pub fn is_passed(
+    threshold: &Threshold,
+    yes: u64,
+    no: u64,
+    abstain: u64,
+    total_weight: u64,
+    expired: bool,
+) -> bool {
+    // a cw4 group can lose all of its members, without any weight nothing passes
+    if total_weight == 0 {
+        return false;
+    }
+    match threshold {
+        Threshold::AbsoluteCount { weight } => yes >= *weight,
+        Threshold::AbsolutePercentage { percentage } => {
+            yes >= votes_needed(total_weight, *percentage)
+        },
+        Threshold::ThresholdQuorum { threshold, quorum } => {
+            let opinions = if expired { yes.saturating_add(no) } else { total_weight.saturating_sub(abstain) };
+            yes.saturating_add(no).saturating_add(abstain) >= votes_needed(total_weight, *quorum)
+                && yes >= votes_needed(opinions, *threshold)
+        },
+    }
+}
+
+// return true if the threshold can't be passed anymore, even if every remaining signer approves
+pub fn is_rejected(threshold: &Threshold, no: u64, abstain: u64, total_weight: u64) -> bool {
+    let yes = total_weight.saturating_sub(no.saturating_add(abstain));
+    !is_passed(threshold, yes, no, abstain, total_weight, false)
+}
+
+// convert a cw3 proposal id into the id of the transaction it refers to
+pub fn to_tx_id(proposal_id: u64) -> StdResult<u16> {
+    u16::try_from(proposal_id).map_err(|_| StdError::not_found("TxData"))
+}
+
+// use the transaction id as the reply id of its dispatch
+pub fn to_reply_id(tx_id: u16) -> u64 {
+    tx_id as u64
+}
+
+// recover the transaction id from the reply id of its dispatch
+pub fn from_reply_id(reply_id: u64) -> u16 {
+    reply_id as u16
+}
+
+// a transfer out of the account, cw20 amounts use the token contract address as denom
+#[derive(Clone, Debug, PartialEq)]
+pub struct Outflow {
+    // none when the funds go to the contract the message instantiates, whose address is not known yet
+    pub recipient: Option<String>,
+    pub amount: Coin,
+}
+
+// list the transfers made by the message, bank sends, funds attached to contract calls and
+// cw20 transfers, sends and allowances
+pub fn msg_outflows(msg: &CosmosMsg) -> Vec<Outflow> {
+    match msg {
+        CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => coin_outflows(to_address, amount),
+        CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, funds }) => {
+            let mut outflows = coin_outflows(contract_addr, funds);
+            outflows.extend(cw20_outflow(contract_addr, msg));
+            outflows
+        },
+        CosmosMsg::Wasm(WasmMsg::Instantiate { funds, .. }) => funds
+            .iter()
+            .map(|amt| Outflow { recipient: None, amount: amt.clone() })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+fn coin_outflows(recipient: &str, amount: &[Coin]) -> Vec<Outflow> {
+    amount
+        .iter()
+        .map(|amt| Outflow { recipient: Some(recipient.to_string()), amount: amt.clone() })
+        .collect()
+}
+
+// decode the cw20 transfer of a contract call, anything that is not a cw20 message moves no tokens
+fn cw20_outflow(contract_addr: &str, msg: &Binary) -> Option<Outflow> {
+    let (recipient, amount) = match from_json(msg) {
+        Ok(Cw20ExecuteMsg::Transfer { recipient, amount }) => (recipient, amount),
+        Ok(Cw20ExecuteMsg::Send { contract, amount, .. }) => (contract, amount),
+        Ok(Cw20ExecuteMsg::IncreaseAllowance { spender, amount, .. }) => (spender, amount),
+        Ok(Cw20ExecuteMsg::TransferFrom { recipient, amount, .. }) => (recipient, amount),
+        _ => return None,
+    };
+    Some(Outflow { recipient: Some(recipient), amount: Coin { denom: contract_addr.to_string(), amount } })
+}
+
+// sum the amount per denom transferred out by the messages
+pub fn total_outflows(msgs: &[CosmosMsg]) -> Vec<Coin> {
+    let mut total: Vec<Coin> = vec![];
+    for outflow in msgs.iter().flat_map(msg_outflows) {
+        match total.iter_mut().find(|c| c.denom == outflow.amount.denom) {
+            Some(sum) => sum.amount += outflow.amount.amount,
+            None => total.push(outflow.amount),
+        }
+    }
+    total
+}
+
+// sum the amount per denom sent by the messages, when every message is a bank send
+pub fn sum_bank_sends(msgs: &[CosmosMsg]) -> Option<Vec<Coin>> {
+    let only_sends = msgs.iter().all(|msg| matches!(msg, CosmosMsg::Bank(BankMsg::Send { .. })));
+    (!msgs.is_empty() && only_sends).then(|| total_outflows(msgs))
+}
+
+// return the type of the message, messages this contract does not know have no type
+pub fn msg_type(msg: &CosmosMsg) -> Option<MsgType> {
+    let msg_type = match msg {
+        CosmosMsg::Bank(BankMsg::Send { .. }) => MsgType::BankSend,
+        CosmosMsg::Bank(BankMsg::Burn { .. }) => MsgType::BankBurn,
+        CosmosMsg::Staking(StakingMsg::Delegate { .. }) => MsgType::StakingDelegate,
+        CosmosMsg::Staking(StakingMsg::Undelegate { .. }) => MsgType::StakingUndelegate,
+        CosmosMsg::Staking(StakingMsg::Redelegate { .. }) => MsgType::StakingRedelegate,
+        CosmosMsg::Distribution(DistributionMsg::SetWithdrawAddress { .. }) => {
+            MsgType::DistributionSetWithdrawAddress
+        },
+        CosmosMsg::Distribution(DistributionMsg::WithdrawDelegatorReward { .. }) => {
+            MsgType::DistributionWithdrawDelegatorReward
+        },
+        CosmosMsg::Wasm(WasmMsg::Execute { .. }) => MsgType::WasmExecute,
+        CosmosMsg::Wasm(WasmMsg::Instantiate { .. }) => MsgType::WasmInstantiate,
+        CosmosMsg::Wasm(WasmMsg::Migrate { .. }) => MsgType::WasmMigrate,
+        CosmosMsg::Wasm(WasmMsg::UpdateAdmin { .. }) => MsgType::WasmUpdateAdmin,
+        CosmosMsg::Wasm(WasmMsg::ClearAdmin { .. }) => MsgType::WasmClearAdmin,
+        CosmosMsg::Custom(_) => MsgType::Custom,
+        _ => return None,
+    };
+    Some(msg_type)
 }
 
-pub fn is_sufficient_signers(threshold: u8, len_signers: usize) -> bool {
-    threshold <= len_signers.try_into().unwrap()
-}
\ No newline at end of file
+// check every message against the allowed message types, the recipient of every transfer against
+// the whitelist, and the total sent per denom across all messages against the transfer limits of the policy
+pub fn check_policy(policy: &Policy, msgs: &[CosmosMsg]) -> Result<(), ContractError> {
+    for msg in msgs {
+        match msg_type(msg) {
+            Some(msg_type) if policy.allowed_msg_types.contains(&msg_type) => {},
+            Some(msg_type) => return Err(ContractError::NotAllowedMsgType { msg_type }),
+            None => return Err(ContractError::UnsupportedMsg {}),
+        }
+        // the address of a new contract is not known yet, so funding it can never pass the whitelist
+        if let CosmosMsg::Wasm(WasmMsg::Instantiate { code_id, funds, .. }) = msg {
+            if policy.whitelist_enabled && !funds.is_empty() {
+                return Err(ContractError::NotAllowedInstantiateFunds { code_id: *code_id });
+            }
+        }
+    }
+
+    for recipient in msgs.iter().flat_map(msg_outflows).filter_map(|outflow| outflow.recipient) {
+        if !policy.can_receive(&recipient) {
+            return Err(ContractError::NotAllowedRecipient {
+                recipient,
+            });
+        }
+    }
+
+    for amt in total_outflows(msgs) {
+        if !policy.can_transfer(amt.clone()) {
+            return Err(ContractError::NotAllowedAmount {
+                amount: amt,
+            });
+        }
+    }
+    Ok(())
+}
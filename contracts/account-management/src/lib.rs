@@ -1,7 +1,10 @@
 pub mod contract;
+pub mod cw3;
+pub mod cw4;
 mod error;
 pub mod execute;
 pub mod helpers;
+pub mod migrate;
 pub mod msg;
 pub mod query;
 pub mod state;
@@ -0,0 +1,100 @@
+use cosmwasm_std::{BlockInfo, Order, StdResult, Storage};
+use cw_utils::{Expiration, Threshold};
+
+use crate::state::{MsgType, Policy, SignerData, State, TxData, TxStatus, POLICY, STATE, TX_EXECUTION};
+
+// storage layouts written by v0.1.0 of the contract
+pub(crate) mod v0_1 {
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+
+    use cosmwasm_std::{Addr, Coin, CosmosMsg};
+    use cw_storage_plus::{Item, Map};
+
+    use crate::state::TxStatus;
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+    pub struct State {
+        pub admin: Addr,
+        pub signers: Vec<Addr>,
+        pub threshold: u8,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+    pub struct TxData {
+        pub id: u16,
+        pub msgs: Vec<CosmosMsg>,
+        pub signers: Vec<Addr>,
+        pub status: Option<TxStatus>,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+    pub struct Policy {
+        pub whitelist_enabled: bool,
+        pub whitelist_addresses: Vec<Addr>,
+        pub transfer_limits: Vec<Coin>,
+    }
+
+    pub const STATE: Item<State> = Item::new("state");
+    pub const TX_EXECUTION: Map<u16, TxData> = Map::new("tx_execution");
+    pub const POLICY: Item<Policy> = Item::new("policy");
+}
+
+// rewrite the v0.1.0 storage into the current layouts, signers get a weight of one and the
+// signer count threshold becomes an absolute count
+pub fn migrate_from_v0_1(storage: &mut dyn Storage, block: &BlockInfo) -> StdResult<()> {
+    let old_state = v0_1::STATE.load(storage)?;
+    let state = State {
+        admin: old_state.admin,
+        signers: old_state
+            .signers
+            .into_iter()
+            .map(|addr| SignerData { addr, weight: 1 })
+            .collect(),
+        threshold: Threshold::AbsoluteCount { weight: old_state.threshold as u64 },
+        default_expiry: None,
+        open_execution: false,
+        self_governed: false,
+        recovery_config: None,
+        group: None,
+        group_weight: 0,
+    };
+    STATE.save(storage, &state)?;
+
+    let old_policy = v0_1::POLICY.load(storage)?;
+    POLICY.save(storage, &Policy {
+        whitelist_enabled: old_policy.whitelist_enabled,
+        whitelist_addresses: old_policy.whitelist_addresses,
+        transfer_limits: old_policy.transfer_limits,
+        timelock: None,
+        allowed_msg_types: MsgType::defaults(),
+    })?;
+
+    let old_txs = v0_1::TX_EXECUTION
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (id, old_tx) in old_txs {
+        // v0.1.0 did not record the proposer, it was always the first signer
+        let mut tx = TxData::new(
+            id,
+            old_tx.msgs,
+            old_tx.signers.first().unwrap_or(&state.admin).clone(),
+            TxStatus::Pending,
+            Expiration::Never {},
+        );
+        // v0.1.0 accepted the same signature more than once, each signer only counts once now
+        tx.signers = vec![];
+        for signer in old_tx.signers {
+            if !tx.signers.contains(&signer) {
+                tx.signers.push(signer);
+            }
+        }
+        tx.status = old_tx.status;
+        if tx.status == Some(TxStatus::Pending) && state.is_passed(&tx, block) {
+            tx.pass(None, block);
+        }
+        TX_EXECUTION.save(storage, tx.id, &tx)?;
+    }
+
+    Ok(())
+}
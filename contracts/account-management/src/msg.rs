@@ -2,49 +2,148 @@ use std::fmt;
 
 use cosmwasm_schema::QueryResponses;
 use cosmwasm_std::{Coin, CosmosMsg, Empty};
+use cw_utils::{Duration, Expiration, Threshold, ThresholdResponse};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::state::TxData;
+use crate::cw3::{
+    ProposalListResponse, ProposalResponse, Vote, VoteListResponse, VoteResponse, VoterListResponse,
+    VoterResponse,
+};
+use crate::cw4::MemberChangedHookMsg;
+use crate::state::{MsgType, Pause, Recovery, RecoveryConfig, Role, SpendWindow, TxData};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Signer {
+    pub addr: String,
+    pub weight: u64,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
-    pub admin: String,
-    pub signers: Vec<String>,
-    pub threshold: u8,
+    // admin manages the account configuration, when it is not set the account is self governed and
+    // configuration changes must be executed by the account itself through an approved transaction
+    pub admin: Option<String>,
+    pub signers: Vec<Signer>,
+    // group is a cw4 group contract the signers and weights are read from, signers must be empty when it is set.
+    // The account must be allowed to add hooks to the group, so it can follow membership changes
+    pub group: Option<String>,
+    pub threshold: Threshold,
     pub whitelist_enabled: bool,
+    // default_expiry is applied to transactions proposed without an explicit expiry
+    pub default_expiry: Option<Duration>,
+    // open_execution allows any account to execute approved transactions, not only signers
+    pub open_execution: bool,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg<T = Empty> 
 where
     T: Clone + fmt::Debug + PartialEq + JsonSchema,
 {
-    // ChangeAdmin will change current admin to new admin, must be called by a current admin
-    ChangeAdmin { new_admin: String },
-    // ChangeThreshold will change current threshold to new threshold, must be called by a current admin
-    ChangeThreshold { new_threshold: u8 },
-    // ChangeWhitelistEnabled will change current whitelist enabled, must be called by a current admin
+    // ChangeAdmin will nominate a new admin until the nomination expires, must be called by a current admin.
+    // The current admin stays in place until the nominee accepts
+    ChangeAdmin { new_admin: String, expires: Option<Expiration> },
+    // AcceptAdmin will make the nominee the current admin, must be called by the pending admin
+    AcceptAdmin {},
+    // CancelAdminTransfer will cancel the pending admin nomination, must be called by a current admin
+    CancelAdminTransfer {},
+    // GrantRole will grant a role to the address, must be called by a current admin
+    GrantRole { address: String, role: Role },
+    // RevokeRole will revoke a role from the address, must be called by a current admin
+    RevokeRole { address: String, role: Role },
+    // ChangeThreshold will change current threshold to new threshold, must be called by an admin or a signer manager.
+    // The threshold is evaluated against the current signers, so percentages adapt to membership changes
+    ChangeThreshold { new_threshold: Threshold },
+    // ChangeWhitelistEnabled will change current whitelist enabled, must be called by an admin or a policy manager
     ChangeWhitelistEnabled { enabled: bool },
-    // AddSigners will add signers to current signers, must be called by an admin
-    AddSigners { signers: Vec<String> },
-    // RemoveSigners will remove signers from current signers, must be called by an admin
+    // AddSigners will add signers to current signers or update their weight, must be called by an admin or a signer manager
+    AddSigners { signers: Vec<Signer> },
+    // RemoveSigners will remove signers from current signers, must be called by an admin or a signer manager
     RemoveSigners { signers: Vec<String> },
-    /// Execute requests the contract to re-dispatch all these messages with the
-    /// contract's address as sender. Every implementation has it's own logic to
-    /// determine in
-    ExecuteTransaction{ msgs: Vec<CosmosMsg<T>> },
-    // SignMessage will sign transaction execution in pending period
+    /// Execute proposes these messages to be re-dispatched with the contract's
+    /// address as sender once the transaction is approved. Every implementation
+    /// has it's own logic to determine in. The transaction can be signed until
+    /// `expires`, falling back to the contract default expiry when it is not set
+    ExecuteTransaction{ msgs: Vec<CosmosMsg<T>>, expires: Option<Expiration> },
+    // SignMessage will sign transaction execution in pending period, until the transaction expires.
+    // The transaction is passed once the threshold is reached
     SignTransaction { tx_id: u16 },
-    // AddWhitelistAddresses will add whitelist addresses to account policy, must be called by an admin
+    // UnsignTransaction will revoke a signature of transaction execution in pending period
+    UnsignTransaction { tx_id: u16 },
+    // RejectTransaction will reject transaction execution in pending period, the transaction is
+    // rejected once the remaining signers can no longer reach the threshold
+    RejectTransaction { tx_id: u16 },
+    // CancelTransaction will cancel transaction execution in pending period or while it waits for
+    // the timelock, must be called by the proposer, an admin or a guardian
+    CancelTransaction { tx_id: u16 },
+    // ExecuteApproved will dispatch the messages of a passed transaction once the timelock has elapsed,
+    // must be called by a signer or an executor unless open execution is enabled
+    ExecuteApproved { tx_id: u16 },
+    // AddWhitelistAddresses will add whitelist addresses to account policy, must be called by an admin or a policy manager
     SetWhitelistAddresses { addresses: Vec<String> },
-    // RemoveWhitelistAddresses will remove whitelist addresses from account policy, must be called by an admin
+    // RemoveWhitelistAddresses will remove whitelist addresses from account policy, must be called by an admin or a policy manager
     RemoveWhitelistAddresses { addresses: Vec<String> },
-    // AddWTransferLimits will add transfer limits to account policy, must be called by an admin
+    // AddWTransferLimits will add transfer limits to account policy, must be called by an admin or a policy manager
     SetTransferLimits { coins: Vec<Coin> },
-    // RemoveTransferLimits will remove transfer limits from account policy, must be called by an admin
+    // RemoveTransferLimits will remove transfer limits from account policy, must be called by an admin or a policy manager
     RemoveTransferLimits { denoms: Vec<String> },
+    // SetTimelock will set the delay between approval and execution to account policy, must be called by an admin or a policy manager
+    SetTimelock { timelock: Option<Duration> },
+    // SetRecoveryConfig will set the guardian threshold and delay of social recovery, must be called by an admin
+    SetRecoveryConfig { config: Option<RecoveryConfig> },
+    // ProposeRecovery will propose to replace the signers and threshold, must be called by a guardian
+    ProposeRecovery { signers: Vec<Signer>, threshold: Threshold },
+    // ApproveRecovery will approve the pending recovery, must be called by a guardian.
+    // The recovery delay starts once the guardian threshold is reached
+    ApproveRecovery {},
+    // VetoRecovery will drop the pending recovery, must be called by a current signer
+    VetoRecovery {},
+    // ExecuteRecovery will replace the signers and threshold once the recovery delay has elapsed,
+    // must be called by a guardian
+    ExecuteRecovery {},
+    // Pause will stop all transactions from being proposed, signed or executed,
+    // must be called by a signer or a guardian
+    Pause {},
+    // Unpause will lift the pause at once when called by an admin, otherwise it records
+    // the vote of the signer until the votes pass the threshold
+    Unpause {},
+    // Propose is the cw3 form of ExecuteTransaction, the proposal can be voted on until `latest`
+    Propose {
+        title: String,
+        description: String,
+        msgs: Vec<CosmosMsg<T>>,
+        latest: Option<Expiration>,
+    },
+    // Vote is the cw3 form of voting on a transaction, yes signs it, no and veto reject it and
+    // abstain only counts towards the quorum
+    Vote { proposal_id: u64, vote: Vote },
+    // Execute is the cw3 form of ExecuteApproved
+    Execute { proposal_id: u64 },
+    // Close will close a pending transaction whose expiry has passed, may be called by anyone
+    Close { proposal_id: u64 },
+    // MemberChangedHook is sent by the cw4 group when its members change, may only be called by the group.
+    // Votes of removed members stop counting the next time a transaction is voted on or executed
+    MemberChangedHook(MemberChangedHookMsg),
+    // SetAllowance will let the signer send up to the limits per period on their own, bank sends
+    // above the allowance go through the normal approval flow. Must be called by an admin or a policy manager
+    SetAllowance { signer: String, limits: Vec<Coin>, period: Duration },
+    // RemoveAllowance will remove the allowance of the signer, must be called by an admin or a policy manager
+    RemoveAllowance { signer: String },
+    // SetTransferWindow will cap the total sent per denom by dispatched transactions in each period,
+    // must be called by an admin or a policy manager
+    SetTransferWindow { limits: Vec<Coin>, period: Duration },
+    // RemoveTransferWindow will remove the transfer window caps, must be called by an admin or a policy manager
+    RemoveTransferWindow {},
+    // SetAllowedMsgTypes will replace the message types transactions may contain, must be called by an admin or a policy manager
+    SetAllowedMsgTypes { msg_types: Vec<MsgType> },
+    // DispatchTransaction will dispatch the messages of an executed transaction at once, so they succeed
+    // or revert together. May only be called by the account itself
+    DispatchTransaction { tx_id: u16 },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, QueryResponses)]
@@ -53,6 +152,9 @@ pub enum QueryMsg {
     #[returns(AdminResponse)]
     Admin {},
 
+    #[returns(PendingAdminResponse)]
+    PendingAdmin {},
+
     #[returns(SignerListResponse)]
     Signerlist {},
 
@@ -70,6 +172,64 @@ pub enum QueryMsg {
 
     #[returns(TransferLimitsResponse)]
     TransferLimits {},
+
+    #[returns(TimelockResponse)]
+    Timelock {},
+
+    #[returns(RolesResponse)]
+    Roles {},
+
+    #[returns(RecoveryConfigResponse)]
+    RecoveryConfig {},
+
+    #[returns(RecoveryResponse)]
+    Recovery {},
+
+    #[returns(PauseStatusResponse)]
+    PauseStatus {},
+
+    #[returns(ProposalResponse)]
+    Proposal { proposal_id: u64 },
+
+    #[returns(ProposalListResponse)]
+    ListProposals {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    #[returns(ProposalListResponse)]
+    ReverseProposals {
+        start_before: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    #[returns(VoteResponse)]
+    Vote { proposal_id: u64, voter: String },
+
+    #[returns(VoteListResponse)]
+    ListVotes {
+        proposal_id: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    #[returns(VoterResponse)]
+    Voter { address: String },
+
+    #[returns(VoterListResponse)]
+    ListVoters {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    #[returns(AllowanceResponse)]
+    Allowance { signer: String },
+
+    #[returns(TransferWindowResponse)]
+    TransferWindow {},
+
+    #[returns(AllowedMsgTypesResponse)]
+    AllowedMsgTypes {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -80,14 +240,15 @@ pub struct AdminResponse {
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
-pub struct SignerListResponse {
-    pub signers: Vec<String>,
+pub struct PendingAdminResponse {
+    pub pending_admin: Option<String>,
+    pub expires: Option<Expiration>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
-pub struct ThresholdResponse {
-    pub threshold: u8,
+pub struct SignerListResponse {
+    pub signers: Vec<Signer>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -112,4 +273,62 @@ pub struct WhitelistAddressesResponse {
 #[serde(rename_all = "snake_case")]
 pub struct TransferLimitsResponse {
     pub transfer_limits: Vec<Coin>,
-}
\ No newline at end of file
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct TimelockResponse {
+    pub timelock: Option<Duration>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct RoleHolder {
+    pub address: String,
+    pub roles: Vec<Role>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct RolesResponse {
+    pub roles: Vec<RoleHolder>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct RecoveryConfigResponse {
+    pub config: Option<RecoveryConfig>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct RecoveryResponse {
+    pub recovery: Option<Recovery>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct PauseStatusResponse {
+    pub paused: bool,
+    pub pause: Option<Pause>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct AllowanceResponse {
+    pub allowance: Option<SpendWindow>,
+    pub remaining: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct TransferWindowResponse {
+    pub window: Option<SpendWindow>,
+    pub remaining: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct AllowedMsgTypesResponse {
+    pub msg_types: Vec<MsgType>,
+}
@@ -1,8 +1,19 @@
-use cosmwasm_std::{Deps, Order, StdResult};
+use cosmwasm_std::{Addr, BlockInfo, Deps, Env, Order, QuerierWrapper, StdResult};
+use cw_storage_plus::Bound;
+use cw_utils::ThresholdResponse;
+use crate::cw3::{
+    ProposalListResponse, ProposalResponse, Status, Vote, VoteInfo, VoteListResponse, VoteResponse,
+    VoterDetail, VoterListResponse, VoterResponse,
+};
+use crate::helpers::{load_members, load_state, load_state_with_signers, to_tx_id};
 use crate::msg::{
-    AdminResponse, SignerListResponse, ThresholdResponse, TransferLimitsResponse, TxExecutionsResponse, WhitelistAddressesResponse, WhitelistEnabledResponse
+    AdminResponse, AllowanceResponse, AllowedMsgTypesResponse, PauseStatusResponse, PendingAdminResponse, RecoveryConfigResponse, RecoveryResponse, RoleHolder, RolesResponse, Signer, SignerListResponse, TimelockResponse, TransferLimitsResponse, TransferWindowResponse, TxExecutionsResponse, WhitelistAddressesResponse, WhitelistEnabledResponse
 };
-use crate::state::{TxData, POLICY, STATE, TX_EXECUTION, TX_NEXT_ID};
+use crate::state::{State, TxData, ALLOWANCES, PAUSE, PENDING_ADMIN, POLICY, RECOVERY, ROLES, STATE, TRANSFER_WINDOW, TX_EXECUTION, TX_NEXT_ID};
+
+// default and maximum page size of the cw3 list queries
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
 
 pub fn admin(deps: Deps) -> StdResult<AdminResponse> {
     let cfg = STATE.load(deps.storage)?;
@@ -12,20 +23,29 @@ pub fn admin(deps: Deps) -> StdResult<AdminResponse> {
     Ok(resp)
 }
 
+pub fn pending_admin(deps: Deps) -> StdResult<PendingAdminResponse> {
+    let pending_admin = PENDING_ADMIN.may_load(deps.storage)?;
+    let resp = PendingAdminResponse{
+        pending_admin: pending_admin.as_ref().map(|p| p.address.to_string()),
+        expires: pending_admin.map(|p| p.expires),
+    };
+    Ok(resp)
+}
+
 pub fn signer_list(deps: Deps) -> StdResult<SignerListResponse> {
-    let cfg = STATE.load(deps.storage)?;
+    let cfg = load_state_with_signers(deps)?;
     let resp = SignerListResponse{
-        signers: cfg.signers.into_iter().map(|a| a.into()).collect(),
+        signers: cfg.signers.into_iter().map(|s| Signer {
+            addr: s.addr.into(),
+            weight: s.weight,
+        }).collect(),
     };
     Ok(resp)
 }
 
 pub fn threshold(deps: Deps) -> StdResult<ThresholdResponse> {
-    let cfg = STATE.load(deps.storage)?;
-    let resp = ThresholdResponse{
-        threshold: cfg.threshold.to_owned(),
-    };
-    Ok(resp)
+    let cfg = load_state(deps)?;
+    Ok(cfg.threshold.to_response(cfg.total_weight()))
 }
 
 pub fn whitelist_enabled(deps: Deps) -> StdResult<WhitelistEnabledResponse> {
@@ -36,7 +56,8 @@ pub fn whitelist_enabled(deps: Deps) -> StdResult<WhitelistEnabledResponse> {
     Ok(resp)
 }
 
-pub fn tx_executions(deps: Deps) -> StdResult<TxExecutionsResponse> {
+pub fn tx_executions(deps: Deps, env: Env) -> StdResult<TxExecutionsResponse> {
+    let mut state = load_state(deps)?;
     let next_id = TX_NEXT_ID.load(deps.storage)?;
     if next_id <= 1 {
         let resp = TxExecutionsResponse{
@@ -44,15 +65,16 @@ pub fn tx_executions(deps: Deps) -> StdResult<TxExecutionsResponse> {
         };
         Ok(resp)
     } else {
-        let data: Vec<TxData> = TX_EXECUTION.range(
+        let mut data: Vec<TxData> = TX_EXECUTION.range(
             deps.storage,
             None,
             None,
             Order::Ascending,
-        ).filter_map(|result| match result {
-            Ok((_, d)) => Some(d.into()),
-            Err(_) => None,
-        }).collect();
+        ).filter_map(|result| result.ok().map(|(_, d)| d)).collect();
+        for d in data.iter_mut() {
+            load_members(&deps.querier, &mut state, d.voters())?;
+            refresh_status(&state, d, &env.block);
+        }
         let resp = TxExecutionsResponse{
             tx_executions: data,
         };
@@ -71,7 +93,236 @@ pub fn whitelist_addresses(deps: Deps) -> StdResult<WhitelistAddressesResponse>
 pub fn transfer_limits(deps: Deps) -> StdResult<TransferLimitsResponse> {
     let policy = POLICY.load(deps.storage)?;
     let resp = TransferLimitsResponse{
-        transfer_limits: policy.transfer_limits.into_iter().map(|a| a.into()).collect(),
+        transfer_limits: policy.transfer_limits,
+    };
+    Ok(resp)
+}
+
+pub fn timelock(deps: Deps) -> StdResult<TimelockResponse> {
+    let policy = POLICY.load(deps.storage)?;
+    let resp = TimelockResponse{
+        timelock: policy.timelock,
     };
     Ok(resp)
-}
\ No newline at end of file
+}
+
+pub fn roles(deps: Deps) -> StdResult<RolesResponse> {
+    let roles = ROLES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(address, roles)| RoleHolder {
+            address: address.into(),
+            roles,
+        }))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(RolesResponse { roles })
+}
+
+pub fn recovery_config(deps: Deps) -> StdResult<RecoveryConfigResponse> {
+    let cfg = STATE.load(deps.storage)?;
+    let resp = RecoveryConfigResponse{
+        config: cfg.recovery_config,
+    };
+    Ok(resp)
+}
+
+pub fn recovery(deps: Deps) -> StdResult<RecoveryResponse> {
+    let resp = RecoveryResponse{
+        recovery: RECOVERY.may_load(deps.storage)?,
+    };
+    Ok(resp)
+}
+
+pub fn pause_status(deps: Deps) -> StdResult<PauseStatusResponse> {
+    let pause = PAUSE.may_load(deps.storage)?;
+    let resp = PauseStatusResponse{
+        paused: pause.is_some(),
+        pause,
+    };
+    Ok(resp)
+}
+
+// show the transaction with the status it has against the current signers, and an expired
+// transaction with the status closing it would give
+fn refresh_status(state: &State, tx: &mut TxData, block: &BlockInfo) {
+    tx.status = state.current_status(tx, block);
+    tx.update_status(block);
+}
+
+fn proposal_response(
+    querier: &QuerierWrapper,
+    state: &mut State,
+    mut tx: TxData,
+    block: &BlockInfo,
+) -> StdResult<ProposalResponse> {
+    load_members(querier, state, tx.voters())?;
+    refresh_status(state, &mut tx, block);
+    Ok(ProposalResponse {
+        id: tx.id as u64,
+        title: tx.title,
+        description: tx.description,
+        msgs: tx.msgs,
+        status: tx.status.as_ref().map_or(Status::Pending, Status::from),
+        expires: tx.expires,
+        threshold: state.threshold.to_response(state.total_weight()),
+    })
+}
+
+// list every vote of the transaction, ordered by voter address
+fn tx_votes(state: &State, tx: &TxData) -> Vec<VoteInfo> {
+    let ballots = [
+        (&tx.signers, Vote::Yes),
+        (&tx.rejections, Vote::No),
+        (&tx.abstentions, Vote::Abstain),
+    ];
+    let mut votes: Vec<VoteInfo> = ballots
+        .into_iter()
+        .flat_map(|(voters, vote)| {
+            voters.iter().map(move |voter| VoteInfo {
+                proposal_id: tx.id as u64,
+                voter: voter.to_string(),
+                vote,
+                weight: state.weight_of(voter).unwrap_or_default(),
+            })
+        })
+        .collect();
+    votes.sort_by(|a, b| a.voter.cmp(&b.voter));
+    votes
+}
+
+pub fn proposal(deps: Deps, env: Env, proposal_id: u64) -> StdResult<ProposalResponse> {
+    let mut cfg = load_state(deps)?;
+    let tx = TX_EXECUTION.load(deps.storage, to_tx_id(proposal_id)?)?;
+    proposal_response(&deps.querier, &mut cfg, tx, &env.block)
+}
+
+pub fn list_proposals(
+    deps: Deps,
+    env: Env,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ProposalListResponse> {
+    let mut cfg = load_state(deps)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    // ids beyond the transaction id range leave nothing to list
+    let start = match start_after {
+        Some(id) => match u16::try_from(id) {
+            Ok(id) => Some(Bound::exclusive(id)),
+            Err(_) => return Ok(ProposalListResponse { proposals: vec![] }),
+        },
+        None => None,
+    };
+    let proposals = TX_EXECUTION
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.and_then(|(_, tx)| proposal_response(&deps.querier, &mut cfg, tx, &env.block)))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ProposalListResponse { proposals })
+}
+
+pub fn reverse_proposals(
+    deps: Deps,
+    env: Env,
+    start_before: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ProposalListResponse> {
+    let mut cfg = load_state(deps)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    // ids beyond the transaction id range are above every transaction
+    let end = start_before
+        .and_then(|id| u16::try_from(id).ok())
+        .map(Bound::exclusive);
+    let proposals = TX_EXECUTION
+        .range(deps.storage, None, end, Order::Descending)
+        .take(limit)
+        .map(|item| item.and_then(|(_, tx)| proposal_response(&deps.querier, &mut cfg, tx, &env.block)))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ProposalListResponse { proposals })
+}
+
+pub fn vote(deps: Deps, proposal_id: u64, voter: String) -> StdResult<VoteResponse> {
+    let mut cfg = load_state(deps)?;
+    let tx = TX_EXECUTION.load(deps.storage, to_tx_id(proposal_id)?)?;
+    load_members(&deps.querier, &mut cfg, tx.voters())?;
+    let vote = tx_votes(&cfg, &tx).into_iter().find(|v| v.voter == voter);
+    Ok(VoteResponse { vote })
+}
+
+pub fn list_votes(
+    deps: Deps,
+    proposal_id: u64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<VoteListResponse> {
+    let mut cfg = load_state(deps)?;
+    let tx = TX_EXECUTION.load(deps.storage, to_tx_id(proposal_id)?)?;
+    load_members(&deps.querier, &mut cfg, tx.voters())?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let votes = tx_votes(&cfg, &tx)
+        .into_iter()
+        .filter(|v| start_after.as_ref().is_none_or(|start| &v.voter > start))
+        .take(limit)
+        .collect();
+    Ok(VoteListResponse { votes })
+}
+
+pub fn voter(deps: Deps, address: String) -> StdResult<VoterResponse> {
+    let mut cfg = load_state(deps)?;
+    load_members(&deps.querier, &mut cfg, [&Addr::unchecked(&address)])?;
+    Ok(VoterResponse {
+        weight: cfg.weight_of(address),
+    })
+}
+
+pub fn list_voters(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<VoterListResponse> {
+    let cfg = load_state_with_signers(deps)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let mut voters: Vec<VoterDetail> = cfg
+        .signers
+        .into_iter()
+        .map(|s| VoterDetail {
+            addr: s.addr.to_string(),
+            weight: s.weight,
+        })
+        .filter(|v| start_after.as_ref().is_none_or(|start| &v.addr > start))
+        .collect();
+    voters.sort_by(|a, b| a.addr.cmp(&b.addr));
+    voters.truncate(limit);
+    Ok(VoterListResponse { voters })
+}
+
+pub fn allowance(deps: Deps, env: Env, signer: String) -> StdResult<AllowanceResponse> {
+    let signer = deps.api.addr_validate(&signer)?;
+    let allowance = ALLOWANCES.may_load(deps.storage, &signer)?.map(|mut a| {
+        a.refresh(&env.block);
+        a
+    });
+    let resp = AllowanceResponse{
+        remaining: allowance.as_ref().map(|a| a.remaining()).unwrap_or_default(),
+        allowance,
+    };
+    Ok(resp)
+}
+
+pub fn transfer_window(deps: Deps, env: Env) -> StdResult<TransferWindowResponse> {
+    let window = TRANSFER_WINDOW.may_load(deps.storage)?.map(|mut w| {
+        w.refresh(&env.block);
+        w
+    });
+    let resp = TransferWindowResponse{
+        remaining: window.as_ref().map(|w| w.remaining()).unwrap_or_default(),
+        window,
+    };
+    Ok(resp)
+}
+
+pub fn allowed_msg_types(deps: Deps) -> StdResult<AllowedMsgTypesResponse> {
+    let policy = POLICY.load(deps.storage)?;
+    let resp = AllowedMsgTypesResponse{
+        msg_types: policy.allowed_msg_types,
+    };
+    Ok(resp)
+}
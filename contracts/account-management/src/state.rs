@@ -1,14 +1,33 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Coin, CosmosMsg};
+use cosmwasm_std::{Addr, BlockInfo, Coin, CosmosMsg, Timestamp, Uint128};
 use cw_storage_plus::{Item, Map};
+use cw_utils::{Duration, Expiration, Threshold};
+
+use crate::error::ContractError;
+use crate::helpers::{is_passed, is_rejected};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SignerData {
+    pub addr: Addr,
+    pub weight: u64,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct State {
     pub admin: Addr,
-    pub signers: Vec<Addr>,
-    pub threshold: u8,
+    pub signers: Vec<SignerData>,
+    pub threshold: Threshold,
+    pub default_expiry: Option<Duration>,
+    pub open_execution: bool,
+    pub self_governed: bool,
+    pub recovery_config: Option<RecoveryConfig>,
+    // cw4 group the signers and weights are read from, instead of the signers stored here
+    pub group: Option<Addr>,
+    // total weight of the cw4 group, read from the group when the state is loaded
+    #[serde(skip)]
+    pub group_weight: u64,
 }
 
 impl State {
@@ -21,7 +40,98 @@ impl State {
     // return true if the address is registered as signer
     pub fn is_signer(&self, addr: impl AsRef<str>) -> bool {
         let addr = addr.as_ref();
-        self.signers.iter().any(|a| a.as_ref() == addr)
+        self.signers.iter().any(|s| s.addr.as_ref() == addr)
+    }
+
+    // return the weight of the address, or None if it is not registered as signer
+    pub fn weight_of(&self, addr: impl AsRef<str>) -> Option<u64> {
+        let addr = addr.as_ref();
+        self.signers.iter().find(|s| s.addr.as_ref() == addr).map(|s| s.weight)
+    }
+
+    // add the given signers, or update the weight of the ones already registered
+    pub fn upsert_signers(&mut self, signers: Vec<SignerData>) {
+        for signer in signers {
+            match self.signers.iter_mut().find(|s| s.addr == signer.addr) {
+                Some(curr_signer) => curr_signer.weight = signer.weight,
+                None => self.signers.push(signer),
+            }
+        }
+    }
+
+    // return the summed weight of all registered signers
+    pub fn total_weight(&self) -> u64 {
+        match self.group {
+            Some(_) => self.group_weight,
+            None => self.signers.iter().fold(0, |total, s| total.saturating_add(s.weight)),
+        }
+    }
+
+    // check the threshold can be reached by the signers. percentage thresholds are valid for any total
+    // weight, so an account without signers is refused here, as nothing would need a vote to pass
+    pub fn validate_threshold(&self, threshold: &Threshold) -> Result<(), ContractError> {
+        let total_weight = match self.group {
+            Some(_) => Some(self.group_weight),
+            None => self.signers.iter().try_fold(0u64, |total, s| total.checked_add(s.weight)),
+        }
+        .ok_or(ContractError::WeightOverflow {})?;
+        if total_weight == 0 {
+            return Err(ContractError::NoSigners {});
+        }
+        threshold.validate(total_weight)?;
+        Ok(())
+    }
+
+    // return the summed weight of the given voters, ignoring non signers
+    pub fn summed_weight(&self, voters: &[Addr]) -> u64 {
+        voters.iter().filter_map(|a| self.weight_of(a)).fold(0, u64::saturating_add)
+    }
+
+    // return true if the approvals of the transaction pass the threshold for the current signers,
+    // abstentions only count towards the quorum
+    pub fn is_passed(&self, tx: &TxData, block: &BlockInfo) -> bool {
+        is_passed(
+            &self.threshold,
+            self.summed_weight(&tx.signers),
+            self.summed_weight(&tx.rejections),
+            self.summed_weight(&tx.abstentions),
+            self.total_weight(),
+            tx.expires.is_expired(block),
+        )
+    }
+
+    // return true if the unpause votes pass the threshold for the current signers
+    pub fn is_unpaused(&self, pause: &Pause) -> bool {
+        is_passed(
+            &self.threshold,
+            self.summed_weight(&pause.unpause_votes),
+            0,
+            0,
+            self.total_weight(),
+            false,
+        )
+    }
+
+    // return true if the rejections and abstentions of the transaction prevent it from ever passing
+    pub fn is_rejected(&self, tx: &TxData) -> bool {
+        is_rejected(
+            &self.threshold,
+            self.summed_weight(&tx.rejections),
+            self.summed_weight(&tx.abstentions),
+            self.total_weight(),
+        )
+    }
+
+    // return the status the transaction has against the current signers and threshold, a passed
+    // transaction goes back to pending when the approvals of the current signers fall short
+    pub fn current_status(&self, tx: &TxData, block: &BlockInfo) -> Option<TxStatus> {
+        match tx.status {
+            Some(TxStatus::Pending) | Some(TxStatus::Passed) if self.is_passed(tx, block) => Some(TxStatus::Passed),
+            Some(TxStatus::Pending) | Some(TxStatus::Passed) if self.is_rejected(tx) => Some(TxStatus::Rejected),
+            Some(TxStatus::Passed) if tx.expires.is_expired(block) => Some(TxStatus::Expired),
+            Some(TxStatus::Passed) => Some(TxStatus::Pending),
+            ref status => status.clone(),
+        }
     }
 
     // return true if the address is registered as admin and the config is mutable
@@ -38,23 +148,139 @@ impl State {
     pub fn can_execute(&self, addr: &str) -> bool {
         self.is_signer(addr)
     }
+
+    // return true if the address can dispatch an approved transaction
+    pub fn can_execute_approved(&self, addr: &str) -> bool {
+        self.open_execution || self.is_signer(addr)
+    }
 }
 
 pub const STATE: Item<State> = Item::new("state");
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingAdmin {
+    pub address: Addr,
+    pub expires: Expiration,
+}
+
+pub const PENDING_ADMIN: Item<PendingAdmin> = Item::new("pending_admin");
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    ManagePolicy,
+    ManageSigners,
+    CancelTransaction,
+    ExecuteApproved,
+}
+
+impl Permission {
+    // return true if the permission changes the account configuration
+    pub fn is_config(&self) -> bool {
+        matches!(self, Permission::ManagePolicy | Permission::ManageSigners)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    PolicyManager,
+    SignerManager,
+    Guardian,
+    Executor,
+}
+
+impl Role {
+    // return the permissions granted by the role
+    pub fn permissions(&self) -> &'static [Permission] {
+        match self {
+            Role::PolicyManager => &[Permission::ManagePolicy],
+            Role::SignerManager => &[Permission::ManageSigners],
+            Role::Guardian => &[Permission::CancelTransaction],
+            Role::Executor => &[Permission::ExecuteApproved],
+        }
+    }
+
+    // return true if the role grants the permission
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.permissions().contains(&permission)
+    }
+}
+
+pub const ROLES: Map<&Addr, Vec<Role>> = Map::new("roles");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RecoveryConfig {
+    // number of guardian approvals needed to start the recovery delay
+    pub threshold: u64,
+    // time left to the signers to veto an approved recovery
+    pub delay: Duration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Recovery {
+    pub proposer: Addr,
+    pub signers: Vec<SignerData>,
+    pub threshold: Threshold,
+    pub approvals: Vec<Addr>,
+    pub executable_at: Option<Expiration>,
+}
+
+impl Recovery {
+    // return true if the recovery is approved and its delay has elapsed
+    pub fn is_executable(&self, block: &BlockInfo) -> bool {
+        self.executable_at.is_some_and(|t| t.is_expired(block))
+    }
+}
+
+pub const RECOVERY: Item<Recovery> = Item::new("recovery");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Pause {
+    pub paused_by: Addr,
+    pub paused_at_height: u64,
+    pub paused_at_time: Timestamp,
+    // signers who voted to lift the pause
+    pub unpause_votes: Vec<Addr>,
+}
+
+pub const PAUSE: Item<Pause> = Item::new("pause");
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub enum TxStatus {
     Pending,
+    Passed,
     Done,
     Failed,
+    Expired,
+    Rejected,
+    Cancelled,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MsgResult {
+    pub msg_index: u32,
+    pub success: bool,
+    pub error: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct TxData {
     pub id: u16,
+    pub title: String,
+    pub description: String,
     pub msgs: Vec<CosmosMsg>,
+    pub proposer: Addr,
     pub signers: Vec<Addr>,
+    pub rejections: Vec<Addr>,
+    pub abstentions: Vec<Addr>,
     pub status: Option<TxStatus>,
+    pub expires: Expiration,
+    pub executable_at: Option<Expiration>,
+    pub results: Vec<MsgResult>,
+    pub error: Option<String>,
+    // dispatched from the allowance of the proposer without a vote
+    pub from_allowance: bool,
 }
 
 impl TxData {
@@ -63,12 +289,57 @@ impl TxData {
         msgs: Vec<CosmosMsg>,
         signer: Addr,
         status: TxStatus,
+        expires: Expiration,
     ) -> Self {
         TxData{
             id,
+            title: String::new(),
+            description: String::new(),
             msgs,
+            proposer: signer.clone(),
             signers: vec![signer],
+            rejections: vec![],
+            abstentions: vec![],
             status: Some(status),
+            expires,
+            executable_at: None,
+            results: vec![],
+            error: None,
+            from_allowance: false,
+        }
+    }
+
+    // mark the transaction as passed, it becomes executable once the timelock has elapsed
+    pub fn pass(&mut self, timelock: Option<Duration>, block: &BlockInfo) {
+        self.status = Some(TxStatus::Passed);
+        self.executable_at = timelock.map(|t| t.after(block));
+    }
+
+    // return true if the timelock of a passed transaction has elapsed
+    pub fn is_executable(&self, block: &BlockInfo) -> bool {
+        self.status == Some(TxStatus::Passed)
+            && self.executable_at.is_none_or(|t| t.is_expired(block))
+    }
+
+    // return every address that signed, rejected or abstained on the transaction
+    pub fn voters(&self) -> impl Iterator<Item = &Addr> {
+        self.signers.iter().chain(&self.rejections).chain(&self.abstentions)
+    }
+
+    // return true if the address already signed, rejected or abstained on the transaction
+    pub fn has_voted(&self, addr: &Addr) -> bool {
+        self.signers.contains(addr) || self.rejections.contains(addr) || self.abstentions.contains(addr)
+    }
+
+    // return true if the transaction is still pending but its expiry has passed
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        self.status == Some(TxStatus::Pending) && self.expires.is_expired(block)
+    }
+
+    // mark the transaction as expired if it can no longer be signed
+    pub fn update_status(&mut self, block: &BlockInfo) {
+        if self.is_expired(block) {
+            self.status = Some(TxStatus::Expired);
         }
     }
 }
@@ -81,6 +352,9 @@ pub struct Policy {
     pub whitelist_enabled: bool,
     pub whitelist_addresses: Vec<Addr>,
     pub transfer_limits: Vec<Coin>,
+    pub timelock: Option<Duration>,
+    // message types transactions may contain
+    pub allowed_msg_types: Vec<MsgType>,
 }
 
 impl Policy {
@@ -98,14 +372,131 @@ impl Policy {
     // return true if the amount can be transfered
     pub fn can_transfer(&self, amt: Coin) -> bool {
         for limit in &self.transfer_limits {
-            if limit.denom == amt.denom {
-                if limit.amount < amt.amount {
-                    return false;
-                }
+            if limit.denom == amt.denom && limit.amount < amt.amount {
+                return false;
             }
         }
         true
     }
 }
 
-pub const POLICY: Item<Policy> = Item::new("policy");
\ No newline at end of file
+pub const POLICY: Item<Policy> = Item::new("policy");
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MsgType {
+    BankSend,
+    BankBurn,
+    StakingDelegate,
+    StakingUndelegate,
+    StakingRedelegate,
+    DistributionSetWithdrawAddress,
+    DistributionWithdrawDelegatorReward,
+    WasmExecute,
+    WasmInstantiate,
+    WasmMigrate,
+    WasmUpdateAdmin,
+    WasmClearAdmin,
+    Custom,
+}
+
+impl MsgType {
+    // message types allowed on new accounts, contract admin and custom messages must be enabled explicitly
+    pub fn defaults() -> Vec<MsgType> {
+        vec![
+            MsgType::BankSend,
+            MsgType::BankBurn,
+            MsgType::StakingDelegate,
+            MsgType::StakingUndelegate,
+            MsgType::StakingRedelegate,
+            MsgType::DistributionSetWithdrawAddress,
+            MsgType::DistributionWithdrawDelegatorReward,
+            MsgType::WasmExecute,
+            MsgType::WasmInstantiate,
+        ]
+    }
+}
+
+// limits on the amount per denom sent in a fixed period, with the amount sent in the current period
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SpendWindow {
+    pub limits: Vec<Coin>,
+    pub period: Duration,
+    pub spent: Vec<Coin>,
+    pub resets_at: Expiration,
+}
+
+impl SpendWindow {
+    pub fn new(limits: Vec<Coin>, period: Duration, block: &BlockInfo) -> Self {
+        SpendWindow {
+            limits,
+            period,
+            spent: vec![],
+            resets_at: period.after(block),
+        }
+    }
+
+    // start a new period with nothing spent once the current one has elapsed
+    pub fn refresh(&mut self, block: &BlockInfo) {
+        if self.resets_at.is_expired(block) {
+            self.spent = vec![];
+            self.resets_at = self.period.after(block);
+        }
+    }
+
+    // return the amount already sent of the denom in the current period
+    pub fn spent_of(&self, denom: &str) -> Uint128 {
+        self.spent.iter().find(|c| c.denom == denom).map_or(Uint128::zero(), |c| c.amount)
+    }
+
+    // return the amount per denom that can still be sent in the current period
+    pub fn remaining(&self) -> Vec<Coin> {
+        self.limits
+            .iter()
+            .map(|limit| Coin {
+                denom: limit.denom.clone(),
+                amount: limit.amount.saturating_sub(self.spent_of(&limit.denom)),
+            })
+            .collect()
+    }
+
+    // return the total sent in the period of the first limited denom the amounts would exceed
+    pub fn exceeded_by(&self, amounts: &[Coin]) -> Option<Coin> {
+        amounts.iter().find_map(|amt| {
+            let limit = self.limits.iter().find(|l| l.denom == amt.denom)?;
+            let total = self.spent_of(&amt.denom) + amt.amount;
+            (total > limit.amount).then(|| Coin { denom: amt.denom.clone(), amount: total })
+        })
+    }
+
+    // return true if the amounts fit in the remaining limits, denoms without a limit can't be sent
+    pub fn can_spend(&self, amounts: &[Coin]) -> bool {
+        amounts.iter().all(|amt| self.limits.iter().any(|l| l.denom == amt.denom))
+            && self.exceeded_by(amounts).is_none()
+    }
+
+    // record the amounts as sent in the current period
+    pub fn spend(&mut self, amounts: &[Coin]) {
+        for amt in amounts {
+            match self.spent.iter_mut().find(|c| c.denom == amt.denom) {
+                Some(spent) => spent.amount += amt.amount,
+                None => self.spent.push(amt.clone()),
+            }
+        }
+    }
+
+    // give back amounts recorded as sent that never left the account
+    pub fn refund(&mut self, amounts: &[Coin]) {
+        for amt in amounts {
+            if let Some(spent) = self.spent.iter_mut().find(|c| c.denom == amt.denom) {
+                spent.amount = spent.amount.saturating_sub(amt.amount);
+            }
+        }
+        self.spent.retain(|c| !c.amount.is_zero());
+    }
+}
+
+// per signer allowance to send on their own without a vote
+pub const ALLOWANCES: Map<&Addr, SpendWindow> = Map::new("allowances");
+// account wide limits on the outflow of dispatched transactions, denoms without a limit are not capped
+pub const TRANSFER_WINDOW: Item<SpendWindow> = Item::new("transfer_window");
\ No newline at end of file